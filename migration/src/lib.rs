@@ -1,12 +1,29 @@
 pub use sea_schema::migration::*;
 
 mod m20220228_000001_migration_data;
+mod m20220228_000002_create_book_tables;
+mod m20220301_000001_create_book_indexes;
+mod m20220520_000001_add_books_random_key;
+mod m20220601_000001_create_saved_searches;
+mod m20220701_000001_add_book_constraints;
+mod m20220710_000001_add_books_tag_ids;
+mod m20220720_000001_create_tag_stats;
+mod sql;
 
 pub struct Migrator;
 
 #[async_trait::async_trait]
 impl MigratorTrait for Migrator {
     fn migrations() -> Vec<Box<dyn MigrationTrait>> {
-        vec![Box::new(m20220228_000001_migration_data::Migration)]
+        vec![
+            Box::new(m20220228_000001_migration_data::Migration),
+            Box::new(m20220228_000002_create_book_tables::Migration),
+            Box::new(m20220301_000001_create_book_indexes::Migration),
+            Box::new(m20220520_000001_add_books_random_key::Migration),
+            Box::new(m20220601_000001_create_saved_searches::Migration),
+            Box::new(m20220701_000001_add_book_constraints::Migration),
+            Box::new(m20220710_000001_add_books_tag_ids::Migration),
+            Box::new(m20220720_000001_create_tag_stats::Migration),
+        ]
     }
 }
@@ -1,11 +1,13 @@
-use futures::{stream, StreamExt};
+use std::collections::{HashMap, HashSet};
+
 use itertools::Itertools;
 use sea_schema::migration::{
-    sea_orm::Statement,
-    sea_query::{self, *},
+    sea_orm::{DbBackend, QueryResult, Statement, Value},
     *,
 };
 
+use crate::m20220228_000002_create_book_tables::create_tables;
+
 pub struct Migration;
 
 impl MigrationName for Migration {
@@ -14,6 +16,20 @@ impl MigrationName for Migration {
     }
 }
 
+/// 한번에 옮기는 예전 작품의 갯수
+const CHUNK_SIZE: i64 = 1000;
+
+/// sqlx에서 parameter 갯수를 `u16::MAX - 1`으로 제한하고 있음
+const MAX_PARAMS: usize = 60000;
+
+/// 단계마다 마지막으로 옮긴 예전 작품의 id, 중간에 실패하면 다음 실행에서 이어서 옮김
+///
+/// 모두 옮기고 확인까지 끝나면 지움
+const CHECKPOINTS: &str = "migrate_data_checkpoints";
+
+/// (kind, name) -> id
+type BookTagIds = HashMap<(String, String), i64>;
+
 fn to_new_tag(kind: &mut String, name: &mut String) {
     if kind == "tag" {
         let (new_kind, new_name) = if name.starts_with("female") {
@@ -31,326 +47,610 @@ fn to_new_tag(kind: &mut String, name: &mut String) {
 
 #[async_trait::async_trait]
 impl MigrationTrait for Migration {
+    /// 모든 단계는 여러번 실행해도 같은 결과가 나옴
     async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
-        // RENAME TABLE book -> old_book
-        /* manager
-        .rename_table(
-            Table::rename()
-                .table(book::Table, old_book::Table)
-                .to_owned(),
+        // 예전 테이블이 없는 새 데이터베이스에서는 옮길 데이터가 없음
+        if !has_old_tables(manager).await? {
+            return Ok(());
+        }
+
+        // 새 테이블을 만드는 마이그레이션은 이 마이그레이션보다 뒤에 있음
+        create_tables(manager).await?;
+
+        execute(
+            manager,
+            &format!(
+                r#"
+                CREATE TABLE IF NOT EXISTS "{CHECKPOINTS}" (
+                    "step" varchar NOT NULL PRIMARY KEY,
+                    "last_book_id" bigint NOT NULL
+                )
+                "#
+            ),
+            [],
         )
-        .await?; */
-
-        // CREATE TABLE book
-        manager
-            .create_table(
-                Table::create()
-                    .table(book::Table)
-                    .if_not_exists()
-                    .col(ColumnDef::new(book::Column::Id).big_integer().primary_key())
-                    .col(ColumnDef::new(book::Column::Title).string().not_null())
-                    .col(ColumnDef::new(book::Column::Kind).string().not_null())
-                    .col(ColumnDef::new(book::Column::Page).integer().not_null())
-                    .col(ColumnDef::new(book::Column::Language).string().not_null())
-                    .col(
-                        ColumnDef::new(book::Column::CreatedAt)
-                            .timestamp_with_time_zone()
-                            .not_null(),
-                    )
-                    .to_owned(),
-            )
-            .await
-            .unwrap();
-
-        // CREATE TABLE book_tag
-        manager
-            .create_table(
-                Table::create()
-                    .table(book_tag::Table)
-                    .if_not_exists()
-                    .col(
-                        ColumnDef::new(book_tag::Column::Id)
-                            .big_integer()
-                            .primary_key()
-                            .auto_increment(),
-                    )
-                    .col(ColumnDef::new(book_tag::Column::Kind).string().not_null())
-                    .col(ColumnDef::new(book_tag::Column::Name).string().not_null())
-                    .to_owned(),
-            )
-            .await
-            .unwrap();
-
-        // CREATE TABLE book_tag_ref
-        manager
-            .create_table(
-                Table::create()
-                    .table(book_tag_ref::Table)
-                    .if_not_exists()
-                    .col(
-                        ColumnDef::new(book_tag_ref::Column::Id)
-                            .big_integer()
-                            .auto_increment()
-                            .primary_key(),
-                    )
-                    .col(
-                        ColumnDef::new(book_tag_ref::Column::BookId)
-                            .big_integer()
-                            .not_null(),
-                    )
-                    .col(
-                        ColumnDef::new(book_tag_ref::Column::BookTagId)
-                            .big_integer()
-                            .not_null(),
-                    )
-                    .index(
-                        Index::create()
-                            
-                            .name("idx-book-id")
-                            .col(Column::BookId),
-                    )
-                    .index(
-                        Index::create()
-                            
-                            .name("idx-book-tag-id")
-                            .col(Column::BookTagId),
-                    )
-                    .foreign_key(
-                        ForeignKey::create()
-                            .name(book_tag_ref::Column::BookId.as_str())
-                            .from(book_tag_ref::Table, book_tag_ref::Column::BookId)
-                            .to(book::Table, book::Column::Id)
-                            .on_delete(ForeignKeyAction::Cascade),
-                    )
-                    .foreign_key(
-                        ForeignKey::create()
-                            .name(book_tag_ref::Column::BookTagId.as_str())
-                            .from(book_tag_ref::Table, book_tag_ref::Column::BookTagId)
-                            .to(book_tag::Table, book_tag::Column::Id)
-                            .on_delete(ForeignKeyAction::Cascade),
-                    )
-                    .to_owned(),
+        .await?;
+
+        migrate_books(manager).await?;
+
+        let book_tag_ids = migrate_book_tags(manager).await?;
+
+        migrate_books_tag_ref(manager, &book_tag_ids).await?;
+
+        verify(manager).await?;
+
+        execute(manager, &format!(r#"DROP TABLE "{CHECKPOINTS}""#), []).await
+    }
+
+    /// 옮긴 데이터만 지움, 예전 테이블은 그대로 남아있음
+    ///
+    /// 옮긴 뒤에 `POST /books`로 추가된 작품들과 그 작품들의 태그는 남김
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        if !has_old_tables(manager).await? {
+            return Ok(());
+        }
+
+        // 모두 되돌릴 때는 새 테이블을 지우는 마이그레이션이 먼저 실행됨
+        if !has_new_tables(manager).await? {
+            return execute(
+                manager,
+                &format!(r#"DROP TABLE IF EXISTS "{CHECKPOINTS}""#),
+                [],
             )
-            .await
-            .unwrap();
+            .await;
+        }
 
-        // TODO: kind에서 artist cg, game cg 등 snake case가 아닌 것들을 snake case로 변경해야함
-        let migrate_books = format!(
-            r#"
+        let books = book::Table.as_str();
+        let book_tags = book_tag::Table.as_str();
+        let books_tag_ref = book_tag_ref::Table.as_str();
+        let old_books = old_book::Table.as_str();
+
+        for query in [
+            format!(
+                r#"DELETE FROM "{books_tag_ref}" WHERE "book_id" IN (SELECT "id" FROM "{old_books}")"#
+            ),
+            format!(r#"DELETE FROM "{books}" WHERE "id" IN (SELECT "id" FROM "{old_books}")"#),
+            // 태그는 예전 작품의 id와 이어지지 않기 때문에 남은 작품이 사용하지 않는 태그만 지움
+            format!(
+                r#"
+                DELETE FROM "{book_tags}"
+                WHERE NOT EXISTS (
+                    SELECT 1 FROM "{books_tag_ref}"
+                    WHERE "{books_tag_ref}"."book_tag_id" = "{book_tags}"."id"
+                )
+                "#
+            ),
+        ] {
+            execute(manager, &query, []).await?;
+        }
+
+        execute(
+            manager,
+            &format!(r#"DROP TABLE IF EXISTS "{CHECKPOINTS}""#),
+            [],
+        )
+        .await
+    }
+}
+
+async fn migrate_books(manager: &SchemaManager<'_>) -> Result<(), DbErr> {
+    let books = book::Table.as_str();
+    let old_books = old_book::Table.as_str();
+
+    // kind와 language는 옮기면서 바꿈
+    let insert_books = format!(
+        r#"
         INSERT INTO
-            {}(id, title, kind, page, language, created_at)
+            "{books}" ("id", "title", "kind", "page", "language", "created_at")
         SELECT
-            id, title, type AS kind, page_count AS page, language, created_at
+            "id",
+            "title",
+            CASE "type"
+                WHEN 'game cg' THEN 'game_cg'
+                WHEN 'artist cg' THEN 'artist_cg'
+                ELSE "type"
+            END,
+            "page_count",
+            CASE "language"
+                WHEN '한국어' THEN 'korean'
+                ELSE "language"
+            END,
+            "created_at"
         FROM
-            {}
-        ON CONFLICT (id)
+            "{old_books}"
+        WHERE
+            "id" > $1 AND "id" <= $2
+        ON CONFLICT ("id")
             DO NOTHING
-        "#,
-            book::Table.as_str(),
-            old_book::Table.as_str()
-        );
+        "#
+    );
 
-        // $1 = 기존 kind
-        // $2 = 바뀐 kind
-        let update_book_kind = |x: &str| {
-            format!(
+    let last_book_id = last_old_book_id(manager).await?;
+    let mut after = checkpoint(manager, "books").await?;
+
+    while let Some(end) = next_chunk_end(manager, after).await? {
+        execute(manager, &insert_books, [after.into(), end.into()]).await?;
+
+        save_checkpoint(manager, "books", end).await?;
+        after = end;
+
+        log::info!("migrate books: {after}/{last_book_id}");
+    }
+
+    // 이전 버전의 마이그레이션은 kind와 language를 바꾸지 않고 옮겼음
+    for (col, old, new) in [
+        ("kind", "game cg", "game_cg"),
+        ("kind", "artist cg", "artist_cg"),
+        ("language", "한국어", "korean"),
+    ] {
+        execute(
+            manager,
+            &format!(r#"UPDATE "{books}" SET "{col}" = $2 WHERE "{col}" = $1"#),
+            [old.into(), new.into()],
+        )
+        .await?;
+    }
+
+    Ok(())
+}
+
+/// 없는 태그만 추가하고, 이미 있던 태그를 포함해서 모든 태그의 id를 반환함
+///
+/// id는 데이터베이스가 정하기 때문에 몇번을 실행해도 sequence와 어긋나지 않음
+async fn migrate_book_tags(manager: &SchemaManager<'_>) -> Result<BookTagIds, DbErr> {
+    let book_tags = book_tag::Table.as_str();
+
+    let mut book_tag_ids = select_book_tag_ids(manager).await?;
+
+    let missing_book_tags = select_new_book_tags(manager)
+        .await?
+        .into_iter()
+        .filter(|tag| !book_tag_ids.contains_key(tag))
+        .collect::<Vec<_>>();
+
+    for chunk in missing_book_tags.chunks(MAX_PARAMS / 2) {
+        let vars = (1..=chunk.len())
+            .map(|i| format!("(${}, ${})", i * 2 - 1, i * 2))
+            .join(",");
+
+        let values = chunk
+            .iter()
+            .flat_map(|(kind, name)| [kind.as_str().into(), name.as_str().into()])
+            .collect::<Vec<Value>>();
+
+        let res = query_all(
+            manager,
+            &format!(
                 r#"
-                UPDATE
-                    {}
-                SET
-                    {x} = $2
-                WHERE
-                    {x} = $1
-                "#,
-                book::Table.as_str()
+                INSERT INTO
+                    "{book_tags}" ("kind", "name")
+                VALUES
+                    {vars}
+                RETURNING "id", "kind", "name"
+                "#
+            ),
+            values,
+        )
+        .await?;
+
+        for res in res {
+            let (id, kind, name) = book_tag_id_of(&res)?;
+            book_tag_ids.insert((kind, name), id);
+        }
+    }
+
+    log::info!(
+        "migrate book tags: {} added, {} total",
+        missing_book_tags.len(),
+        book_tag_ids.len()
+    );
+
+    Ok(book_tag_ids)
+}
+
+async fn migrate_books_tag_ref(
+    manager: &SchemaManager<'_>,
+    book_tag_ids: &BookTagIds,
+) -> Result<(), DbErr> {
+    let books_tag_ref = book_tag_ref::Table.as_str();
+
+    let last_book_id = last_old_book_id(manager).await?;
+    let mut after = checkpoint(manager, "books_tag_ref").await?;
+
+    while let Some(end) = next_chunk_end(manager, after).await? {
+        let refs = select_new_books_tag_ref(manager, after, end, book_tag_ids).await?;
+
+        for chunk in refs.chunks(MAX_PARAMS / 2) {
+            let vars = (1..=chunk.len())
+                .map(|i| format!("(${}, ${})", i * 2 - 1, i * 2))
+                .join(",");
+
+            let values = chunk
+                .iter()
+                .flat_map(|(book_id, book_tag_id)| [(*book_id).into(), (*book_tag_id).into()])
+                .collect::<Vec<Value>>();
+
+            // 이미 연결된 태그는 건너뜀
+            execute(
+                manager,
+                &format!(
+                    r#"
+                    INSERT INTO
+                        "{books_tag_ref}" ("book_id", "book_tag_id")
+                    SELECT
+                        "new_ref"."book_id", "new_ref"."book_tag_id"
+                    FROM
+                        (VALUES {vars}) AS "new_ref" ("book_id", "book_tag_id")
+                    WHERE NOT EXISTS (
+                        SELECT 1 FROM "{books_tag_ref}"
+                        WHERE "{books_tag_ref}"."book_id" = "new_ref"."book_id"
+                            AND "{books_tag_ref}"."book_tag_id" = "new_ref"."book_tag_id"
+                    )
+                    "#
+                ),
+                values,
             )
-        };
+            .await?;
+        }
 
-        let select_distinct_old_book_tags = format!(
-            r#"
-            SELECT DISTINCT
-                type, name
-            FROM
-                {}
-        "#,
-            old_book_tag::Table.as_str()
-        );
+        save_checkpoint(manager, "books_tag_ref", end).await?;
+        after = end;
+
+        log::info!("migrate books tag ref: {after}/{last_book_id}");
+    }
+
+    Ok(())
+}
 
-        let select_old_book_tags = format!(
+/// 예전 테이블과 새 테이블의 작품 수, 태그 수, 작품마다 연결된 태그 수를 비교함
+///
+/// 하나라도 다르면 마이그레이션을 실패시킴
+async fn verify(manager: &SchemaManager<'_>) -> Result<(), DbErr> {
+    let books = book::Table.as_str();
+    let old_books = old_book::Table.as_str();
+    let books_tag_ref = book_tag_ref::Table.as_str();
+
+    let mut errors = Vec::new();
+
+    let old_book_count = count(
+        manager,
+        &format!(r#"SELECT COUNT(*) AS "count" FROM "{old_books}""#),
+        [],
+    )
+    .await?;
+    let book_count = count(
+        manager,
+        &format!(
             r#"
-                SELECT
-                    *
-                FROM
-                    {}
-            "#,
-            old_book_tag::Table.as_str()
-        );
+            SELECT COUNT(*) AS "count" FROM "{old_books}"
+            WHERE EXISTS (SELECT 1 FROM "{books}" WHERE "{books}"."id" = "{old_books}"."id")
+            "#
+        ),
+        [],
+    )
+    .await?;
+
+    if old_book_count != book_count {
+        errors.push(format!(
+            "books: expected {old_book_count}, migrated {book_count}"
+        ));
+    }
 
-        manager
-            .get_connection()
-            .execute(Statement::from_string(
-                manager.get_database_backend(),
-                migrate_books,
-            ))
-            .await
-            .unwrap();
-
-        for (col, old, new) in [
-            ("kind", "game cg", "game_cg"),
-            ("kind", "artist cg", "artist_cg"),
-            ("language", "한국어", "korean"),
-        ] {
-            manager
-                .get_connection()
-                .execute(Statement::from_sql_and_values(
-                    manager.get_database_backend(),
-                    &update_book_kind(col),
-                    [old.into(), new.into()],
-                ))
-                .await
-                .unwrap();
+    // 옮기는 단계와 상관없이 데이터베이스에서 다시 읽음
+    let book_tag_ids = select_book_tag_ids(manager).await?;
+    let new_book_tags = select_new_book_tags(manager).await?;
+    let missing_book_tags = new_book_tags
+        .iter()
+        .filter(|tag| !book_tag_ids.contains_key(*tag))
+        .count();
+
+    if missing_book_tags > 0 {
+        errors.push(format!(
+            "book_tags: expected {}, missing {missing_book_tags}",
+            new_book_tags.len()
+        ));
+    }
+
+    let (mut expected_refs, mut migrated_refs) = (0, 0);
+    let mut after = i64::MIN;
+
+    while let Some(end) = next_chunk_end(manager, after).await? {
+        // 태그가 없으면 위에서 이미 실패함
+        if missing_book_tags == 0 {
+            expected_refs += select_new_books_tag_ref(manager, after, end, &book_tag_ids)
+                .await?
+                .len() as i64;
         }
 
-        let old_book_tags = manager
-            .get_connection()
-            .query_all(Statement::from_string(
-                manager.get_database_backend(),
-                select_distinct_old_book_tags,
-            ))
-            .await
-            .unwrap();
+        // 구간 사이의 id로 `POST /books`에서 추가된 작품들의 태그는 세지 않음
+        migrated_refs += count(
+            manager,
+            &format!(
+                r#"
+                SELECT COUNT(*) AS "count" FROM "{books_tag_ref}"
+                WHERE "book_id" IN (
+                    SELECT "id" FROM "{old_books}"
+                    WHERE "id" > $1 AND "id" <= $2
+                )
+                "#
+            ),
+            [after.into(), end.into()],
+        )
+        .await?;
 
-        let mut new_book_tags = Vec::new();
+        after = end;
+    }
 
-        for (i, old_book_tag) in old_book_tags.into_iter().enumerate() {
-            let mut kind = old_book_tag.try_get::<String>("", "type").unwrap();
-            let mut name = old_book_tag.try_get::<String>("", "name").unwrap();
+    if missing_book_tags == 0 && expected_refs != migrated_refs {
+        errors.push(format!(
+            "books_tag_ref: expected {expected_refs}, migrated {migrated_refs}"
+        ));
+    }
 
-            to_new_tag(&mut kind, &mut name);
+    log::info!(
+        "verify migrated data: books {book_count}/{old_book_count}, book_tags {}/{}, books_tag_ref {migrated_refs}/{expected_refs}",
+        new_book_tags.len() - missing_book_tags,
+        new_book_tags.len()
+    );
 
-            new_book_tags.push(((i + 1) as i64, kind, name));
-        }
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(DbErr::Custom(format!(
+            "verify migrated data: {}",
+            errors.join("; ")
+        )))
+    }
+}
 
-        // sqlx에서 parameter 갯수를 `u16::MAX - 1`으로 제한하고 있음
-        // 한번 insert하는데 3개의 파라미터를 넣으니까 대충 20000개씩 나눠서 쿼리날리면됨
-        let seperated = new_book_tags.chunks(20000);
-
-        for new_book_tags in seperated.map(|x| x.iter().cloned()) {
-            let (vars, values): (Vec<_>, Vec<_>) = new_book_tags
-                .into_iter()
-                .enumerate()
-                .map(|(i, x)| ((i + 1) * 3, x))
-                .map(|(i, x)| {
-                    (
-                        format!("(${}, ${}, ${})", i - 2, i - 1, i),
-                        vec![(x.0, (x.1, x.2))],
-                    )
-                })
-                .unzip();
+/// 새 태그 테이블의 (kind, name) -> id
+async fn select_book_tag_ids(manager: &SchemaManager<'_>) -> Result<BookTagIds, DbErr> {
+    let book_tags = book_tag::Table.as_str();
+
+    query_all(
+        manager,
+        &format!(r#"SELECT "id", "kind", "name" FROM "{book_tags}""#),
+        [],
+    )
+    .await?
+    .iter()
+    .map(|res| book_tag_id_of(res).map(|(id, kind, name)| ((kind, name), id)))
+    .collect()
+}
 
-            let vars = vars.join(",");
-            let values = values.into_iter().flat_map(|x| {
-                x.into_iter()
-                    .flat_map(|x| vec![x.0.into(), x.1 .0.into(), x.1 .1.into()])
-            });
+fn book_tag_id_of(res: &QueryResult) -> Result<(i64, String, String), DbErr> {
+    Ok((
+        res.try_get::<i64>("", "id")?,
+        res.try_get::<String>("", "kind")?,
+        res.try_get::<String>("", "name")?,
+    ))
+}
 
-            let insert_book_tags = format!(
-                r#"
-                INSERT INTO
-                    {}(id, kind, name)
-                VALUES
-                    {}
-                ON CONFLICT (id)
-                    DO NOTHING
-            "#,
-                book_tag::Table.as_str(),
-                vars
-            );
-
-            manager
-                .get_connection()
-                .execute(Statement::from_sql_and_values(
-                    manager.get_database_backend(),
-                    &insert_book_tags,
-                    values,
-                ))
-                .await
-                .unwrap();
-        }
+/// 예전 태그들을 새 태그 형식으로 바꾼 (kind, name), 바꾼 뒤에 같아진 태그는 하나만 남김
+async fn select_new_book_tags(manager: &SchemaManager<'_>) -> Result<Vec<(String, String)>, DbErr> {
+    let old_book_tags = old_book_tag::Table.as_str();
+
+    let res = query_all(
+        manager,
+        &format!(r#"SELECT DISTINCT "type", "name" FROM "{old_book_tags}""#),
+        [],
+    )
+    .await?;
+
+    res.into_iter()
+        .map(|res| {
+            let mut kind = res.try_get::<String>("", "type")?;
+            let mut name = res.try_get::<String>("", "name")?;
 
-        let old_book_tags = manager
-            .get_connection()
-            .query_all(Statement::from_string(
-                manager.get_database_backend(),
-                select_old_book_tags,
-            ))
-            .await
-            .unwrap();
+            to_new_tag(&mut kind, &mut name);
 
-        let mut books_tag_ref = Vec::new();
+            Ok((kind, name))
+        })
+        .collect::<Result<HashSet<_>, DbErr>>()
+        .map(|tags| tags.into_iter().collect())
+}
 
-        for old_book_tag in old_book_tags {
-            let book_id = old_book_tag.try_get::<i32>("", "fk_book_id").unwrap();
-            let mut kind = old_book_tag.try_get::<String>("", "type").unwrap();
-            let mut name = old_book_tag.try_get::<String>("", "name").unwrap();
+/// `after < book_id <= end`인 예전 작품들에 붙어있던 태그들의 (book_id, book_tag_id)
+async fn select_new_books_tag_ref(
+    manager: &SchemaManager<'_>,
+    after: i64,
+    end: i64,
+    book_tag_ids: &BookTagIds,
+) -> Result<Vec<(i64, i64)>, DbErr> {
+    let old_book_tags = old_book_tag::Table.as_str();
+
+    let res = query_all(
+        manager,
+        &format!(
+            r#"
+            SELECT "fk_book_id", "type", "name" FROM "{old_book_tags}"
+            WHERE "fk_book_id" > $1 AND "fk_book_id" <= $2
+            "#
+        ),
+        [after.into(), end.into()],
+    )
+    .await?;
+
+    res.into_iter()
+        .map(|res| {
+            let book_id = res.try_get::<i32>("", "fk_book_id")?;
+            let mut kind = res.try_get::<String>("", "type")?;
+            let mut name = res.try_get::<String>("", "name")?;
 
             to_new_tag(&mut kind, &mut name);
 
-            let tag_id = new_book_tags
-                .iter()
-                .find(|(_id, kind_a, name_a)| kind_a == &kind && name_a == &name)
-                .unwrap()
-                .0;
+            match book_tag_ids.get(&(kind, name)) {
+                Some(book_tag_id) => Ok((book_id as i64, *book_tag_id)),
+                None => Err(DbErr::Custom(format!(
+                    "not found book tag of book {book_id}"
+                ))),
+            }
+        })
+        .collect::<Result<HashSet<_>, DbErr>>()
+        .map(|refs| refs.into_iter().collect())
+}
 
-            books_tag_ref.push((book_id as i64, tag_id as i64));
-        }
+/// 예전 작품 중 `after`보다 큰 id부터 `CHUNK_SIZE`개를 옮길 때 마지막 id, 남은 작품이 없으면 None
+async fn next_chunk_end(manager: &SchemaManager<'_>, after: i64) -> Result<Option<i64>, DbErr> {
+    let old_books = old_book::Table.as_str();
+
+    let res = query_all(
+        manager,
+        &format!(
+            r#"
+            SELECT CAST(MAX("id") AS bigint) AS "end" FROM (
+                SELECT "id" FROM "{old_books}"
+                WHERE "id" > $1
+                ORDER BY "id"
+                LIMIT {CHUNK_SIZE}
+            ) AS "chunk"
+            "#
+        ),
+        [after.into()],
+    )
+    .await?;
+
+    match res.first() {
+        Some(res) => res.try_get::<Option<i64>>("", "end"),
+        None => Ok(None),
+    }
+}
 
-        let seperated = books_tag_ref.chunks(30000);
+async fn last_old_book_id(manager: &SchemaManager<'_>) -> Result<i64, DbErr> {
+    let old_books = old_book::Table.as_str();
 
-        stream::iter(seperated.map(|x| x.to_vec()))
-            .for_each_concurrent(5, |books_tag_ref| async {
-                let (vars, values): (Vec<_>, Vec<_>) = books_tag_ref
-                    .into_iter()
-                    .enumerate()
-                    .map(|(i, x)| ((i + 1) * 2, x))
-                    .map(|(i, x)| (format!("(${}, ${})", i - 1, i), vec![x.0, x.1]))
-                    .unzip();
+    count(
+        manager,
+        &format!(r#"SELECT COALESCE(CAST(MAX("id") AS bigint), 0) AS "count" FROM "{old_books}""#),
+        [],
+    )
+    .await
+}
 
-                let vars = vars.join(",");
-                let values = values
-                    .into_iter()
-                    .flat_map(|x| x.into_iter().map(Into::into));
+/// 처음 실행하면 모든 예전 작품의 id보다 작은 값
+async fn checkpoint(manager: &SchemaManager<'_>, step: &str) -> Result<i64, DbErr> {
+    let res = query_all(
+        manager,
+        &format!(r#"SELECT "last_book_id" FROM "{CHECKPOINTS}" WHERE "step" = $1"#),
+        [step.into()],
+    )
+    .await?;
+
+    match res.first() {
+        Some(res) => res.try_get::<i64>("", "last_book_id"),
+        None => Ok(i64::MIN),
+    }
+}
 
-                let insert_book_tags = format!(
-                    r#"
-                    INSERT INTO
-                        {}(book_id, book_tag_id)
-                    VALUES
-                        {}
-                "#,
-                    book_tag_ref::Table.as_str(),
-                    vars
-                );
-
-                println!("{insert_book_tags}");
-
-                manager
-                    .get_connection()
-                    .execute(Statement::from_sql_and_values(
-                        manager.get_database_backend(),
-                        &insert_book_tags,
-                        values,
-                    ))
-                    .await
-                    .unwrap();
-            })
-            .await;
+async fn save_checkpoint(
+    manager: &SchemaManager<'_>,
+    step: &str,
+    last_book_id: i64,
+) -> Result<(), DbErr> {
+    execute(
+        manager,
+        &format!(
+            r#"
+            INSERT INTO "{CHECKPOINTS}" ("step", "last_book_id")
+            VALUES ($1, $2)
+            ON CONFLICT ("step")
+                DO UPDATE SET "last_book_id" = EXCLUDED."last_book_id"
+            "#
+        ),
+        [step.into(), last_book_id.into()],
+    )
+    .await
+}
 
-        Ok(())
+/// "count" 컬럼을 읽음
+async fn count(
+    manager: &SchemaManager<'_>,
+    query: &str,
+    values: impl IntoIterator<Item = Value>,
+) -> Result<i64, DbErr> {
+    let res = query_all(manager, query, values).await?;
+
+    match res.first() {
+        Some(res) => res.try_get::<i64>("", "count"),
+        None => Ok(0),
     }
+}
 
-    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
-        todo!()
+async fn execute(
+    manager: &SchemaManager<'_>,
+    query: &str,
+    values: impl IntoIterator<Item = Value>,
+) -> Result<(), DbErr> {
+    let backend = manager.get_database_backend();
+
+    manager
+        .get_connection()
+        .execute(Statement::from_sql_and_values(backend, query, values))
+        .await?;
+
+    Ok(())
+}
+
+async fn query_all(
+    manager: &SchemaManager<'_>,
+    query: &str,
+    values: impl IntoIterator<Item = Value>,
+) -> Result<Vec<QueryResult>, DbErr> {
+    let backend = manager.get_database_backend();
+
+    manager
+        .get_connection()
+        .query_all(Statement::from_sql_and_values(backend, query, values))
+        .await
+}
+
+/// 예전 테이블은 postgresql에만 있음
+async fn has_old_tables(manager: &SchemaManager<'_>) -> Result<bool, DbErr> {
+    if manager.get_database_backend() != DbBackend::Postgres {
+        return Ok(false);
+    }
+
+    has_tables(
+        manager,
+        &[old_book::Table.as_str(), old_book_tag::Table.as_str()],
+    )
+    .await
+}
+
+/// 예전 테이블이 있을 때만 확인하기 때문에 postgresql에서만 사용함
+async fn has_new_tables(manager: &SchemaManager<'_>) -> Result<bool, DbErr> {
+    has_tables(
+        manager,
+        &[
+            book::Table.as_str(),
+            book_tag::Table.as_str(),
+            book_tag_ref::Table.as_str(),
+        ],
+    )
+    .await
+}
+
+/// 테이블이 모두 있는지 `to_regclass`로 확인함
+async fn has_tables(manager: &SchemaManager<'_>, tables: &[&str]) -> Result<bool, DbErr> {
+    let query = format!(
+        r#"SELECT {} AS "exists""#,
+        tables
+            .iter()
+            .map(|table| format!(r#"to_regclass('"{table}"') IS NOT NULL"#))
+            .join(" AND ")
+    );
+
+    let res = manager
+        .get_connection()
+        .query_one(Statement::from_string(
+            manager.get_database_backend(),
+            query,
+        ))
+        .await?;
+
+    match res {
+        Some(res) => res.try_get::<bool>("", "exists"),
+        None => Ok(false),
     }
 }
 
@@ -521,3 +821,147 @@ mod old_book_tag {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    use sea_schema::migration::sea_orm::{ConnectOptions, Database, DatabaseConnection};
+
+    use super::*;
+    use crate::Migrator;
+
+    async fn execute_sql(db: &DatabaseConnection, query: &str) {
+        db.execute(Statement::from_string(
+            db.get_database_backend(),
+            query.to_string(),
+        ))
+        .await
+        .unwrap();
+    }
+
+    async fn count_sql(db: &DatabaseConnection, query: &str) -> i64 {
+        db.query_one(Statement::from_string(
+            db.get_database_backend(),
+            query.to_string(),
+        ))
+        .await
+        .unwrap()
+        .unwrap()
+        .try_get::<i64>("", "count")
+        .unwrap()
+    }
+
+    async fn up_down_up(db: &DatabaseConnection) {
+        Migrator::up(db, None).await.unwrap();
+        Migrator::down(db, None).await.unwrap();
+        Migrator::up(db, None).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn up_down_up_on_sqlite() {
+        let mut option = ConnectOptions::new("sqlite::memory:".to_string());
+        // 연결마다 다른 데이터베이스가 만들어짐
+        option.max_connections(1);
+
+        let db = Database::connect(option).await.unwrap();
+
+        up_down_up(&db).await;
+
+        assert_eq!(
+            count_sql(&db, r#"SELECT COUNT(*) AS "count" FROM "books""#).await,
+            0
+        );
+    }
+
+    /// `POSTGRES_TEST_URL`이 없으면 건너뜀
+    ///
+    /// 다른 테이블과 섞이지 않도록 새 schema에서 실행하고 지움
+    #[tokio::test]
+    async fn up_down_up_with_old_tables_on_postgresql() {
+        let url = match std::env::var("POSTGRES_TEST_URL") {
+            Ok(url) => url,
+            Err(_) => return,
+        };
+
+        let schema = format!(
+            "migrate_data_test_{}",
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap()
+                .as_nanos()
+        );
+
+        let mut option = ConnectOptions::new(url);
+        // search_path는 연결마다 다름
+        option.max_connections(1);
+
+        let db = Database::connect(option).await.unwrap();
+
+        for query in [
+            format!(r#"CREATE SCHEMA "{schema}""#),
+            format!(r#"SET search_path TO "{schema}""#),
+            r#"
+            CREATE TABLE "book" (
+                "id" integer NOT NULL PRIMARY KEY,
+                "title" varchar NOT NULL,
+                "type" varchar NOT NULL,
+                "page_count" integer NOT NULL,
+                "language" varchar NOT NULL,
+                "created_at" timestamp with time zone NOT NULL
+            )
+            "#
+            .to_string(),
+            r#"
+            CREATE TABLE "book_metadata" (
+                "fk_book_id" integer NOT NULL,
+                "type" varchar NOT NULL,
+                "name" varchar NOT NULL
+            )
+            "#
+            .to_string(),
+            r#"
+            INSERT INTO "book" VALUES
+                (1, 'a', 'game cg', 10, '한국어', now()),
+                (5, 'b', 'manga', 20, 'english', now())
+            "#
+            .to_string(),
+            r#"
+            INSERT INTO "book_metadata" VALUES
+                (1, 'tag', 'female glasses'),
+                (1, 'artist', 'someone'),
+                (5, 'tag', 'full color')
+            "#
+            .to_string(),
+        ] {
+            execute_sql(&db, &query).await;
+        }
+
+        up_down_up(&db).await;
+
+        let books = count_sql(&db, r#"SELECT COUNT(*) AS "count" FROM "books""#).await;
+        let refs = count_sql(&db, r#"SELECT COUNT(*) AS "count" FROM "books_tag_ref""#).await;
+
+        // 예전 작품들 사이의 id로 추가된 작품은 확인할 때 세지 않음
+        for query in [
+            r#"
+            INSERT INTO "books" ("id", "title", "kind", "page", "language", "created_at")
+            VALUES (3, 'c', 'manga', 30, 'korean', now())
+            "#,
+            r#"
+            INSERT INTO "books_tag_ref" ("book_id", "book_tag_id")
+            SELECT 3, MIN("id") FROM "book_tags"
+            "#,
+        ] {
+            execute_sql(&db, query).await;
+        }
+
+        let verified = verify(&SchemaManager::new(&db)).await;
+
+        execute_sql(&db, &format!(r#"DROP SCHEMA "{schema}" CASCADE"#)).await;
+
+        assert_eq!(books, 2);
+        assert_eq!(refs, 3);
+        verified.unwrap();
+    }
+}
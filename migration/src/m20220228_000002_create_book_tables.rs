@@ -0,0 +1,99 @@
+use sea_schema::migration::{sea_orm::DbBackend, *};
+
+use crate::sql::execute_all;
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m20220228_000002_create_book_tables"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    /// 이미 적용된 `m20220228_000001_migrate_data`보다 뒤에 있어야 기존 데이터베이스에서 순서가 어긋나지 않음
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        create_tables(manager).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let stmts = [
+            r#"DROP TABLE IF EXISTS "books_tag_ref""#,
+            r#"DROP TABLE IF EXISTS "book_tags""#,
+            r#"DROP TABLE IF EXISTS "books""#,
+        ];
+
+        execute_all(manager, stmts.map(String::from)).await
+    }
+}
+
+/// 마이그레이션이 생기기 전에 서버가 시작할 때 만들던 테이블과 같음
+///
+/// 예전 데이터를 옮길 때도 먼저 만들기 때문에 이미 있으면 건너뜀
+pub async fn create_tables(manager: &SchemaManager<'_>) -> Result<(), DbErr> {
+    let stmts = match manager.get_database_backend() {
+        // sqlite는 `insert_book_tags`에서 `ON CONFLICT`를 사용하기 때문에 처음부터 unique가 필요함
+        DbBackend::Sqlite => [
+            r#"
+            CREATE TABLE IF NOT EXISTS "books" (
+                "id" INTEGER NOT NULL PRIMARY KEY,
+                "title" TEXT NOT NULL,
+                "kind" TEXT NOT NULL,
+                "page" INTEGER NOT NULL,
+                "language" TEXT NOT NULL,
+                "created_at" TEXT NOT NULL
+            )
+            "#,
+            r#"
+            CREATE TABLE IF NOT EXISTS "book_tags" (
+                "id" INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+                "kind" TEXT NOT NULL,
+                "name" TEXT NOT NULL,
+                UNIQUE ("kind", "name")
+            )
+            "#,
+            r#"
+            CREATE TABLE IF NOT EXISTS "books_tag_ref" (
+                "id" INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+                "book_id" INTEGER NOT NULL
+                    REFERENCES "books" ("id") ON DELETE CASCADE,
+                "book_tag_id" INTEGER NOT NULL
+                    REFERENCES "book_tags" ("id") ON DELETE CASCADE
+            )
+            "#,
+        ],
+        _ => [
+            r#"
+            CREATE TABLE IF NOT EXISTS "books" (
+                "id" bigint NOT NULL PRIMARY KEY,
+                "title" varchar NOT NULL,
+                "kind" varchar NOT NULL,
+                "page" integer NOT NULL,
+                "language" varchar NOT NULL,
+                "created_at" timestamp with time zone NOT NULL
+            )
+            "#,
+            r#"
+            CREATE TABLE IF NOT EXISTS "book_tags" (
+                "id" bigserial NOT NULL PRIMARY KEY,
+                "kind" varchar NOT NULL,
+                "name" varchar NOT NULL
+            )
+            "#,
+            r#"
+            CREATE TABLE IF NOT EXISTS "books_tag_ref" (
+                "id" bigserial NOT NULL PRIMARY KEY,
+                "book_id" bigint NOT NULL,
+                "book_tag_id" bigint NOT NULL,
+                CONSTRAINT "book_id" FOREIGN KEY ("book_id")
+                    REFERENCES "books" ("id") ON DELETE CASCADE,
+                CONSTRAINT "book_tag_id" FOREIGN KEY ("book_tag_id")
+                    REFERENCES "book_tags" ("id") ON DELETE CASCADE
+            )
+            "#,
+        ],
+    };
+
+    execute_all(manager, stmts.map(String::from)).await
+}
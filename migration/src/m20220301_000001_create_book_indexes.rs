@@ -0,0 +1,37 @@
+use sea_schema::migration::*;
+
+use crate::sql::execute_all;
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m20220301_000001_create_book_indexes"
+    }
+}
+
+/// (index, table, column)
+const INDEXES: [(&str, &str, &str); 3] = [
+    ("idx-name", "book_tags", "name"),
+    ("idx-book-id", "books_tag_ref", "book_id"),
+    ("idx-book-tag-id", "books_tag_ref", "book_tag_id"),
+];
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let stmts = INDEXES.iter().map(|(index, table, column)| {
+            format!(r#"CREATE INDEX IF NOT EXISTS "{index}" ON "{table}" ("{column}")"#)
+        });
+
+        execute_all(manager, stmts).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let stmts = INDEXES
+            .iter()
+            .map(|(index, _, _)| format!(r#"DROP INDEX IF EXISTS "{index}""#));
+
+        execute_all(manager, stmts).await
+    }
+}
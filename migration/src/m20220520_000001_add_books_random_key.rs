@@ -0,0 +1,73 @@
+use sea_schema::migration::{sea_orm::DbBackend, *};
+
+use crate::sql::execute_all;
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m20220520_000001_add_books_random_key"
+    }
+}
+
+/// `[0, 1)` 범위의 난수
+const SQLITE_RANDOM: &str = "((random() & 9007199254740991) / 9007199254740992.0)";
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    /// 무작위 정렬에서 사용하는 작품마다 미리 정해둔 난수
+    ///
+    /// 이미 있는 작품들도 각자 다른 값을 갖도록 volatile한 기본값을 사용함
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let stmts = match manager.get_database_backend() {
+            // sqlite는 상수가 아닌 기본값으로 컬럼을 추가할 수 없어서 trigger로 채움
+            DbBackend::Sqlite => vec![
+                r#"ALTER TABLE "books" ADD COLUMN "random_key" REAL NOT NULL DEFAULT 0"#
+                    .to_string(),
+                format!(r#"UPDATE "books" SET "random_key" = {SQLITE_RANDOM}"#),
+                format!(
+                    r#"
+                    CREATE TRIGGER IF NOT EXISTS "books-random-key"
+                        AFTER INSERT ON "books"
+                    BEGIN
+                        UPDATE "books" SET "random_key" = {SQLITE_RANDOM}
+                        WHERE "id" = NEW."id";
+                    END
+                    "#
+                ),
+            ],
+            _ => vec![r#"
+                ALTER TABLE "books"
+                    ADD COLUMN IF NOT EXISTS "random_key" DOUBLE PRECISION NOT NULL DEFAULT random()
+                "#
+            .to_string()],
+        };
+
+        execute_all(manager, stmts).await?;
+
+        execute_all(
+            manager,
+            [
+                r#"CREATE INDEX IF NOT EXISTS "idx-random-key" ON "books" ("random_key", "id")"#
+                    .to_string(),
+            ],
+        )
+        .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let stmts = [
+            r#"DROP TRIGGER IF EXISTS "books-random-key""#,
+            r#"DROP INDEX IF EXISTS "idx-random-key""#,
+            r#"ALTER TABLE "books" DROP COLUMN "random_key""#,
+        ];
+
+        // postgresql에는 trigger를 만들지 않았음
+        let stmts = match manager.get_database_backend() {
+            DbBackend::Sqlite => &stmts[..],
+            _ => &stmts[1..],
+        };
+
+        execute_all(manager, stmts.iter().map(|x| x.to_string())).await
+    }
+}
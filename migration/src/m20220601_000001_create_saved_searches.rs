@@ -0,0 +1,60 @@
+use sea_schema::migration::{sea_orm::DbBackend, *};
+
+use crate::sql::execute_all;
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m20220601_000001_create_saved_searches"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let create_table = match manager.get_database_backend() {
+            DbBackend::Sqlite => {
+                r#"
+                CREATE TABLE IF NOT EXISTS "saved_searches" (
+                    "id" INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+                    "user_id" BLOB NOT NULL,
+                    "name" TEXT NOT NULL,
+                    "filter" TEXT NOT NULL,
+                    "last_seen_max_id" INTEGER NOT NULL DEFAULT 0,
+                    "created_at" TEXT NOT NULL,
+                    "updated_at" TEXT NOT NULL
+                )
+                "#
+            }
+            _ => {
+                r#"
+                CREATE TABLE IF NOT EXISTS "saved_searches" (
+                    "id" bigserial NOT NULL PRIMARY KEY,
+                    "user_id" uuid NOT NULL,
+                    "name" varchar NOT NULL,
+                    "filter" text NOT NULL,
+                    "last_seen_max_id" bigint NOT NULL DEFAULT 0,
+                    "created_at" timestamp with time zone NOT NULL,
+                    "updated_at" timestamp with time zone NOT NULL
+                )
+                "#
+            }
+        };
+
+        let stmts = [
+            create_table,
+            r#"CREATE INDEX IF NOT EXISTS "idx-user-id" ON "saved_searches" ("user_id")"#,
+        ];
+
+        execute_all(manager, stmts.map(String::from)).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        execute_all(
+            manager,
+            [r#"DROP TABLE IF EXISTS "saved_searches""#.to_string()],
+        )
+        .await
+    }
+}
@@ -0,0 +1,218 @@
+use sea_schema::migration::{sea_orm::DbBackend, *};
+
+use crate::sql::execute_all;
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m20220701_000001_add_book_constraints"
+    }
+}
+
+const BOOK_KINDS: &str = "'manga', 'doujinshi', 'artist_cg', 'game_cg'";
+
+const BOOK_TAG_KINDS: &str = "'artist', 'series', 'group', 'character', 'female', 'male', 'misc'";
+
+/// 같은 `(kind, name)` 태그 중에 id가 가장 작은 태그만 남김
+const DEDUPLICATED_BOOK_TAGS: &str = r#"
+    SELECT
+        "id",
+        MIN("id") OVER (PARTITION BY "kind", "name") AS "keep_id"
+    FROM
+        "book_tags"
+"#;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    /// 중복된 데이터를 먼저 합치고 constraint를 추가함
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let stmts = match manager.get_database_backend() {
+            // sqlite는 처음부터 `(kind, name)`이 unique이고, constraint를 추가할 수 없어서 index와 trigger를 사용함
+            DbBackend::Sqlite => vec![
+                r#"
+                DELETE FROM "books_tag_ref"
+                WHERE "id" NOT IN (
+                    SELECT MIN("id") FROM "books_tag_ref" GROUP BY "book_id", "book_tag_id"
+                )
+                "#
+                .to_string(),
+                r#"
+                CREATE UNIQUE INDEX IF NOT EXISTS "uk-books-tag-ref-book-id-book-tag-id"
+                    ON "books_tag_ref" ("book_id", "book_tag_id")
+                "#
+                .to_string(),
+                format!(
+                    r#"
+                    CREATE TRIGGER IF NOT EXISTS "ck-books-kind"
+                        BEFORE INSERT ON "books"
+                        WHEN NEW."kind" NOT IN ({BOOK_KINDS})
+                    BEGIN
+                        SELECT RAISE(ABORT, 'CHECK constraint failed: ck-books-kind');
+                    END
+                    "#
+                ),
+                format!(
+                    r#"
+                    CREATE TRIGGER IF NOT EXISTS "ck-book-tags-kind"
+                        BEFORE INSERT ON "book_tags"
+                        WHEN NEW."kind" NOT IN ({BOOK_TAG_KINDS})
+                    BEGIN
+                        SELECT RAISE(ABORT, 'CHECK constraint failed: ck-book-tags-kind');
+                    END
+                    "#
+                ),
+                // CHECK constraint처럼 바꿀 때도 검사함
+                format!(
+                    r#"
+                    CREATE TRIGGER IF NOT EXISTS "ck-books-kind-update"
+                        BEFORE UPDATE OF "kind" ON "books"
+                        WHEN NEW."kind" NOT IN ({BOOK_KINDS})
+                    BEGIN
+                        SELECT RAISE(ABORT, 'CHECK constraint failed: ck-books-kind');
+                    END
+                    "#
+                ),
+                format!(
+                    r#"
+                    CREATE TRIGGER IF NOT EXISTS "ck-book-tags-kind-update"
+                        BEFORE UPDATE OF "kind" ON "book_tags"
+                        WHEN NEW."kind" NOT IN ({BOOK_TAG_KINDS})
+                    BEGIN
+                        SELECT RAISE(ABORT, 'CHECK constraint failed: ck-book-tags-kind');
+                    END
+                    "#
+                ),
+            ],
+            _ => vec![
+                // 중복된 태그를 가리키던 연결을 남길 태그로 옮김
+                format!(
+                    r#"
+                    UPDATE "books_tag_ref"
+                    SET "book_tag_id" = "deduplicated"."keep_id"
+                    FROM ({DEDUPLICATED_BOOK_TAGS}) AS "deduplicated"
+                    WHERE "books_tag_ref"."book_tag_id" = "deduplicated"."id"
+                        AND "deduplicated"."id" <> "deduplicated"."keep_id"
+                    "#
+                ),
+                format!(
+                    r#"
+                    DELETE FROM "book_tags"
+                    USING ({DEDUPLICATED_BOOK_TAGS}) AS "deduplicated"
+                    WHERE "book_tags"."id" = "deduplicated"."id"
+                        AND "deduplicated"."id" <> "deduplicated"."keep_id"
+                    "#
+                ),
+                // 태그를 합치면서 생긴 것까지 포함해서 같은 연결은 하나만 남김
+                r#"
+                DELETE FROM "books_tag_ref" AS "a"
+                USING "books_tag_ref" AS "b"
+                WHERE "a"."book_id" = "b"."book_id"
+                    AND "a"."book_tag_id" = "b"."book_tag_id"
+                    AND "a"."id" > "b"."id"
+                "#
+                .to_string(),
+                r#"
+                ALTER TABLE "book_tags"
+                    ADD CONSTRAINT "uk-book-tags-kind-name" UNIQUE ("kind", "name")
+                "#
+                .to_string(),
+                r#"
+                ALTER TABLE "books_tag_ref"
+                    ADD CONSTRAINT "uk-books-tag-ref-book-id-book-tag-id"
+                        UNIQUE ("book_id", "book_tag_id")
+                "#
+                .to_string(),
+                format!(
+                    r#"
+                    ALTER TABLE "books"
+                        ADD CONSTRAINT "ck-books-kind" CHECK ("kind" IN ({BOOK_KINDS}))
+                    "#
+                ),
+                format!(
+                    r#"
+                    ALTER TABLE "book_tags"
+                        ADD CONSTRAINT "ck-book-tags-kind" CHECK ("kind" IN ({BOOK_TAG_KINDS}))
+                    "#
+                ),
+            ],
+        };
+
+        execute_all(manager, stmts).await
+    }
+
+    /// 합친 데이터는 되돌리지 않음
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let stmts = match manager.get_database_backend() {
+            DbBackend::Sqlite => [
+                r#"DROP TRIGGER IF EXISTS "ck-book-tags-kind-update""#,
+                r#"DROP TRIGGER IF EXISTS "ck-books-kind-update""#,
+                r#"DROP TRIGGER IF EXISTS "ck-book-tags-kind""#,
+                r#"DROP TRIGGER IF EXISTS "ck-books-kind""#,
+                r#"DROP INDEX IF EXISTS "uk-books-tag-ref-book-id-book-tag-id""#,
+            ]
+            .to_vec(),
+            _ => [
+                r#"ALTER TABLE "book_tags" DROP CONSTRAINT IF EXISTS "ck-book-tags-kind""#,
+                r#"ALTER TABLE "books" DROP CONSTRAINT IF EXISTS "ck-books-kind""#,
+                r#"
+                ALTER TABLE "books_tag_ref"
+                    DROP CONSTRAINT IF EXISTS "uk-books-tag-ref-book-id-book-tag-id"
+                "#,
+                r#"ALTER TABLE "book_tags" DROP CONSTRAINT IF EXISTS "uk-book-tags-kind-name""#,
+            ]
+            .to_vec(),
+        };
+
+        execute_all(manager, stmts.into_iter().map(String::from)).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sea_schema::migration::sea_orm::{ConnectOptions, Database, Statement};
+
+    use crate::Migrator;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn sqlite_checks_kind_on_update() {
+        let mut option = ConnectOptions::new("sqlite::memory:".to_string());
+        // 연결마다 다른 데이터베이스가 만들어짐
+        option.max_connections(1);
+
+        let db = Database::connect(option).await.unwrap();
+
+        Migrator::up(&db, None).await.unwrap();
+
+        let execute =
+            |query: &str| db.execute(Statement::from_string(DbBackend::Sqlite, query.to_string()));
+
+        execute(
+            r#"
+            INSERT INTO "books" ("id", "title", "kind", "page", "language", "created_at")
+            VALUES (1, 'a', 'manga', 1, 'korean', '2022-07-01T00:00:00Z')
+            "#,
+        )
+        .await
+        .unwrap();
+        execute(r#"INSERT INTO "book_tags" ("kind", "name") VALUES ('misc', 'a')"#)
+            .await
+            .unwrap();
+
+        assert!(execute(r#"UPDATE "books" SET "kind" = 'unknown'"#)
+            .await
+            .is_err());
+        assert!(execute(r#"UPDATE "book_tags" SET "kind" = 'unknown'"#)
+            .await
+            .is_err());
+
+        execute(r#"UPDATE "books" SET "kind" = 'doujinshi'"#)
+            .await
+            .unwrap();
+        execute(r#"UPDATE "book_tags" SET "kind" = 'artist'"#)
+            .await
+            .unwrap();
+    }
+}
@@ -0,0 +1,106 @@
+use sea_schema::migration::{sea_orm::DbBackend, *};
+
+use crate::sql::execute_all;
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m20220710_000001_add_books_tag_ids"
+    }
+}
+
+/// `"book_id"`가 바뀐 작품들의 `"tag_ids"`를 `"books_tag_ref"`에서 다시 만듦
+const SYNC_TAG_IDS: &str = r#"
+    UPDATE "books"
+    SET "tag_ids" = ARRAY(
+        SELECT "book_tag_id" FROM "books_tag_ref"
+        WHERE "books_tag_ref"."book_id" = "books"."id"
+        ORDER BY "book_tag_id"
+    )
+"#;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    /// 여러 태그로 필터링할 때 join 대신 배열 연산자와 GIN 인덱스를 사용하기 위해 작품에 붙은 태그의 id들을 같이 저장함
+    ///
+    /// `"books_tag_ref"`를 바꾸는 모든 경로에서 맞춰지도록 trigger로 유지함
+    ///
+    /// sqlite에는 배열이 없어서 그대로 `"books_tag_ref"`를 사용함
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        if manager.get_database_backend() == DbBackend::Sqlite {
+            return Ok(());
+        }
+
+        let stmts = vec![
+            r#"
+            ALTER TABLE "books"
+                ADD COLUMN IF NOT EXISTS "tag_ids" BIGINT[] NOT NULL DEFAULT '{}'
+            "#
+            .to_string(),
+            SYNC_TAG_IDS.to_string(),
+            r#"CREATE INDEX IF NOT EXISTS "idx-tag-ids" ON "books" USING GIN ("tag_ids")"#
+                .to_string(),
+            // 한 statement에서 바뀐 연결들을 모아서 작품마다 한번만 갱신함
+            format!(
+                r#"
+                CREATE OR REPLACE FUNCTION "books_sync_tag_ids"() RETURNS TRIGGER AS $$
+                BEGIN
+                    IF TG_OP IN ('INSERT', 'UPDATE') THEN
+                        {SYNC_TAG_IDS}
+                        WHERE "books"."id" IN (SELECT "book_id" FROM "new_refs");
+                    END IF;
+
+                    IF TG_OP IN ('DELETE', 'UPDATE') THEN
+                        {SYNC_TAG_IDS}
+                        WHERE "books"."id" IN (SELECT "book_id" FROM "old_refs");
+                    END IF;
+
+                    RETURN NULL;
+                END;
+                $$ LANGUAGE plpgsql
+                "#
+            ),
+            r#"
+            CREATE TRIGGER "books-tag-ids-insert"
+                AFTER INSERT ON "books_tag_ref"
+                REFERENCING NEW TABLE AS "new_refs"
+                FOR EACH STATEMENT EXECUTE FUNCTION "books_sync_tag_ids"()
+            "#
+            .to_string(),
+            r#"
+            CREATE TRIGGER "books-tag-ids-update"
+                AFTER UPDATE ON "books_tag_ref"
+                REFERENCING OLD TABLE AS "old_refs" NEW TABLE AS "new_refs"
+                FOR EACH STATEMENT EXECUTE FUNCTION "books_sync_tag_ids"()
+            "#
+            .to_string(),
+            r#"
+            CREATE TRIGGER "books-tag-ids-delete"
+                AFTER DELETE ON "books_tag_ref"
+                REFERENCING OLD TABLE AS "old_refs"
+                FOR EACH STATEMENT EXECUTE FUNCTION "books_sync_tag_ids"()
+            "#
+            .to_string(),
+        ];
+
+        execute_all(manager, stmts).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        if manager.get_database_backend() == DbBackend::Sqlite {
+            return Ok(());
+        }
+
+        let stmts = [
+            r#"DROP TRIGGER IF EXISTS "books-tag-ids-delete" ON "books_tag_ref""#,
+            r#"DROP TRIGGER IF EXISTS "books-tag-ids-update" ON "books_tag_ref""#,
+            r#"DROP TRIGGER IF EXISTS "books-tag-ids-insert" ON "books_tag_ref""#,
+            r#"DROP FUNCTION IF EXISTS "books_sync_tag_ids"()"#,
+            r#"DROP INDEX IF EXISTS "idx-tag-ids""#,
+            r#"ALTER TABLE "books" DROP COLUMN IF EXISTS "tag_ids""#,
+        ];
+
+        execute_all(manager, stmts.iter().map(|x| x.to_string())).await
+    }
+}
@@ -0,0 +1,78 @@
+use sea_schema::migration::{sea_orm::DbBackend, *};
+
+use crate::sql::execute_all;
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m20220720_000001_create_tag_stats"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    /// 태그마다 작품 종류와 언어별 작품 수를 미리 세어둠
+    ///
+    /// 작품을 추가할 때 같이 갱신하고, 이미 있는 작품들은 여기서 한번 셈
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let create_table = match manager.get_database_backend() {
+            DbBackend::Sqlite => {
+                r#"
+                CREATE TABLE IF NOT EXISTS "tag_stats" (
+                    "book_tag_id" INTEGER NOT NULL
+                        REFERENCES "book_tags" ("id") ON DELETE CASCADE,
+                    "book_kind" TEXT NOT NULL,
+                    "language" TEXT NOT NULL,
+                    "book_count" INTEGER NOT NULL,
+                    "latest_created_at" TEXT NOT NULL,
+                    PRIMARY KEY ("book_tag_id", "book_kind", "language")
+                )
+                "#
+            }
+            _ => {
+                r#"
+                CREATE TABLE IF NOT EXISTS "tag_stats" (
+                    "book_tag_id" bigint NOT NULL,
+                    "book_kind" varchar NOT NULL,
+                    "language" varchar NOT NULL,
+                    "book_count" bigint NOT NULL,
+                    "latest_created_at" timestamp with time zone NOT NULL,
+                    PRIMARY KEY ("book_tag_id", "book_kind", "language"),
+                    CONSTRAINT "book_tag_id" FOREIGN KEY ("book_tag_id")
+                        REFERENCES "book_tags" ("id") ON DELETE CASCADE
+                )
+                "#
+            }
+        };
+
+        execute_all(
+            manager,
+            [
+                create_table.to_string(),
+                r#"
+                INSERT INTO "tag_stats"
+                    ("book_tag_id", "book_kind", "language", "book_count", "latest_created_at")
+                SELECT
+                    "books_tag_ref"."book_tag_id",
+                    "books"."kind",
+                    "books"."language",
+                    COUNT(*),
+                    MAX("books"."created_at")
+                FROM
+                    "books_tag_ref"
+                INNER JOIN "books"
+                    ON "books"."id" = "books_tag_ref"."book_id"
+                GROUP BY
+                    "books_tag_ref"."book_tag_id", "books"."kind", "books"."language"
+                "#
+                .to_string(),
+            ],
+        )
+        .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        execute_all(manager, [r#"DROP TABLE IF EXISTS "tag_stats""#.to_string()]).await
+    }
+}
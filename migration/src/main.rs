@@ -1,7 +1,7 @@
 use migration::Migrator;
 use sea_schema::migration::*;
 
-#[async_std::main]
+#[tokio::main]
 async fn main() {
     cli::run_cli(Migrator).await;
 
@@ -9,8 +9,6 @@ async fn main() {
 
     let url = std::env::var("DATABASE_URL").unwrap();
 
-    println!("url = {}", url);
-
     let db = Database::connect(url).await.unwrap();
 
     Migrator::up(&db, None).await.unwrap(); */
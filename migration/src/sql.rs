@@ -0,0 +1,16 @@
+use sea_schema::migration::{sea_orm::Statement, DbErr, SchemaManager};
+
+/// 데이터베이스마다 문법이 달라서 raw sql을 사용함
+pub async fn execute_all(
+    manager: &SchemaManager<'_>,
+    stmts: impl IntoIterator<Item = String>,
+) -> Result<(), DbErr> {
+    let db = manager.get_connection();
+    let backend = manager.get_database_backend();
+
+    for stmt in stmts {
+        db.execute(Statement::from_string(backend, stmt)).await?;
+    }
+
+    Ok(())
+}
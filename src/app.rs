@@ -2,22 +2,28 @@ use std::sync::Arc;
 use std::time::{Duration, SystemTime};
 use std::{convert::Infallible, net::SocketAddr};
 
-use hyper::Server;
 use hyper::{
     body::Body,
     http::{Request, Response},
     service::{make_service_fn, service_fn},
 };
+use hyper::{header, Server};
 use inspect::{Inspect, InspectOk};
 use sai::{Component, ComponentLifecycle, Injected};
 use tokio::sync::oneshot;
 use util::elapse;
 
+use crate::compression;
 use crate::config::Config;
 use crate::model::{Model, Presenter};
 use crate::msg::Msg;
 use crate::repository::RepositorySet;
-use crate::usecase::{get_book, get_books, get_books_by_ids};
+use crate::usecase::{
+    create_saved_search, delete_saved_search, get_book, get_book_cache_stats, get_book_facets,
+    get_book_tags, get_books, get_books_by_ids, get_random_book, get_saved_search,
+    get_saved_search_books, get_saved_searches, lookup_books, recompute_tag_stats,
+    update_saved_search,
+};
 
 #[derive(Component)]
 pub struct Resolver {
@@ -43,6 +49,52 @@ impl Resolver {
             }
 
             Msg::GetBook(payload) => get_book::execute(payload, repository).await?.into(),
+
+            Msg::GetRandomBook(payload) => {
+                get_random_book::execute(payload, repository).await?.into()
+            }
+
+            Msg::GetBookFacets(payload) => {
+                get_book_facets::execute(payload, repository).await?.into()
+            }
+
+            Msg::GetBookTags(payload) => get_book_tags::execute(payload, repository).await?.into(),
+
+            Msg::LookupBooks(payload) => lookup_books::execute(payload, repository).await?.into(),
+
+            Msg::GetSavedSearches(payload) => get_saved_searches::execute(payload, repository)
+                .await?
+                .into(),
+
+            Msg::GetSavedSearch(payload) => {
+                get_saved_search::execute(payload, repository).await?.into()
+            }
+
+            Msg::CreateSavedSearch(payload) => create_saved_search::execute(payload, repository)
+                .await?
+                .into(),
+
+            Msg::UpdateSavedSearch(payload) => update_saved_search::execute(payload, repository)
+                .await?
+                .into(),
+
+            Msg::DeleteSavedSearch(payload) => delete_saved_search::execute(payload, repository)
+                .await?
+                .into(),
+
+            Msg::GetSavedSearchBooks(payload) => {
+                get_saved_search_books::execute(payload, repository)
+                    .await?
+                    .into()
+            }
+
+            Msg::RecomputeTagStats(payload) => recompute_tag_stats::execute(payload, repository)
+                .await?
+                .into(),
+
+            Msg::GetBookCacheStats(payload) => get_book_cache_stats::execute(payload, repository)
+                .await?
+                .into(),
         };
 
         Ok(model)
@@ -89,6 +141,11 @@ async fn service(
 ) -> Result<Response<Body>, Infallible> {
     let req_method = request.method().to_owned();
     let req_uri = request.uri().to_string();
+    let accept_encoding = request
+        .headers()
+        .get(header::ACCEPT_ENCODING)
+        .and_then(|x| x.to_str().ok())
+        .map(str::to_owned);
 
     log::info!("--> {} {}", req_method, req_uri);
 
@@ -108,11 +165,16 @@ async fn service(
 
     if let Err(err) = ret {
         err.inspect(|e| log::error!("{}", e))
-            .set_response(&mut request, &mut response, config)
+            .set_response(&mut request, &mut response, config.clone())
             .await
             .expect("in err.set_response()");
     }
 
+    elapse!(
+        "compress",
+        compression::compress(accept_encoding.as_deref(), &mut response, &config).await
+    );
+
     Ok(response).inspect_ok(|res| {
         log::info!(
             "<-- {} {} {} {}ms",
@@ -0,0 +1,193 @@
+use std::{
+    borrow::Cow,
+    io::{self, Write},
+};
+
+use brotli::CompressorWriter;
+use flate2::{write::GzEncoder, Compression};
+use hyper::{
+    body,
+    header::{self, HeaderValue},
+    Body, Response, StatusCode,
+};
+
+use crate::config::Config;
+
+/// 이보다 큰 본문은 blocking 스레드에서 압축함, 바이트 단위
+const BLOCKING_COMPRESSION_SIZE: usize = 64 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Encoding {
+    Brotli,
+    Gzip,
+}
+
+impl Encoding {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Brotli => "br",
+            Self::Gzip => "gzip",
+        }
+    }
+
+    /// 같은 가중치면 더 작게 압축되는 brotli를 사용함
+    ///
+    /// `Accept-Encoding: gzip, br;q=0.8` -> gzip
+    pub fn negotiate(accept_encoding: &str) -> Option<Self> {
+        let mut brotli = None;
+        let mut gzip = None;
+        let mut any = None;
+
+        for x in accept_encoding.split(',') {
+            let mut x = x.split(';').map(str::trim);
+            let coding = x.next().unwrap_or_default().to_ascii_lowercase();
+            let q = x
+                .find_map(|param| param.strip_prefix("q="))
+                .and_then(|q| q.parse::<f32>().ok())
+                .unwrap_or(1.0);
+
+            match coding.as_str() {
+                "br" => brotli = Some(q),
+                "gzip" | "x-gzip" => gzip = Some(q),
+                "*" => any = Some(q),
+                _ => {}
+            }
+        }
+
+        let brotli = brotli.or(any).unwrap_or(0.0);
+        let gzip = gzip.or(any).unwrap_or(0.0);
+
+        if brotli <= 0.0 && gzip <= 0.0 {
+            None
+        } else if brotli >= gzip {
+            Some(Self::Brotli)
+        } else {
+            Some(Self::Gzip)
+        }
+    }
+
+    /// 인코딩마다 본문이 다르기 때문에 strong ETag를 유지하면서 인코딩을 붙여 구분함
+    ///
+    /// `"1a-0123456789abcdef"` -> `"1a-0123456789abcdef-br"`
+    pub fn tag_etag(&self, etag: &str) -> Option<String> {
+        let etag = etag.strip_suffix('"')?;
+
+        Some(format!("{etag}-{}\"", self.as_str()))
+    }
+
+    /// `tag_etag`로 붙인 인코딩을 뗌, `If-None-Match`를 압축하기 전의 ETag와 비교할 때 사용함
+    pub fn untag_etag(etag: &str) -> Cow<'_, str> {
+        [Self::Brotli, Self::Gzip]
+            .into_iter()
+            .find_map(|encoding| {
+                let tagged = etag.strip_suffix(&format!("-{}\"", encoding.as_str()))?;
+
+                Some(Cow::Owned(format!("{tagged}\"")))
+            })
+            .unwrap_or(Cow::Borrowed(etag))
+    }
+
+    fn compress(&self, data: &[u8]) -> io::Result<Vec<u8>> {
+        match self {
+            Self::Brotli => {
+                // quality는 속도와 압축률 사이에서 타협한 값
+                let mut w = CompressorWriter::new(Vec::new(), 4096, 5, 22);
+                w.write_all(data)?;
+                Ok(w.into_inner())
+            }
+            Self::Gzip => {
+                let mut w = GzEncoder::new(Vec::new(), Compression::default());
+                w.write_all(data)?;
+                w.finish()
+            }
+        }
+    }
+}
+
+/// `len` 바이트의 본문을 압축할 인코딩, 압축하지 않으면 None
+///
+/// 304 응답은 본문이 없어서 압축하지 않지만, 200 응답과 같은 ETag를 보내기 위해 같이 사용함
+pub fn encoding_of(accept_encoding: Option<&str>, len: usize, config: &Config) -> Option<Encoding> {
+    if !config.compression() || len < config.compression_min_size() {
+        return None;
+    }
+
+    accept_encoding.and_then(Encoding::negotiate)
+}
+
+/// `COMPRESSION=false`이거나, 본문이 `COMPRESSION_MIN_SIZE`보다 작으면 그대로 응답함
+pub async fn compress(accept_encoding: Option<&str>, resp: &mut Response<Body>, config: &Config) {
+    if !config.compression() {
+        return;
+    }
+
+    if resp.status() == StatusCode::NO_CONTENT
+        || resp.headers().contains_key(header::CONTENT_ENCODING)
+    {
+        return;
+    }
+
+    // 인코딩에 따라 응답이 달라질 수 있음
+    resp.headers_mut()
+        .append(header::VARY, HeaderValue::from_static("accept-encoding"));
+
+    // 304 응답의 ETag에는 `http_cache::set_json_body`에서 인코딩을 붙임
+    if resp.status() == StatusCode::NOT_MODIFIED {
+        return;
+    }
+
+    // 응답 본문은 모두 메모리에 있음
+    let data = match body::to_bytes(std::mem::take(resp.body_mut())).await {
+        Ok(data) => data,
+        Err(err) => {
+            log::error!("read response body: {}", err);
+            return;
+        }
+    };
+
+    let encoding = match encoding_of(accept_encoding, data.len(), config) {
+        Some(encoding) => encoding,
+        None => {
+            *resp.body_mut() = data.into();
+            return;
+        }
+    };
+
+    // 큰 본문을 압축하는 동안 다른 요청을 처리하는 스레드를 막지 않음
+    let compressed = if data.len() >= BLOCKING_COMPRESSION_SIZE {
+        let input = data.clone();
+
+        tokio::task::spawn_blocking(move || encoding.compress(&input))
+            .await
+            .unwrap_or_else(|err| Err(io::Error::new(io::ErrorKind::Other, err)))
+    } else {
+        encoding.compress(&data)
+    };
+
+    let compressed = match compressed {
+        Ok(compressed) => compressed,
+        Err(err) => {
+            log::warn!("compress response body with {}: {}", encoding.as_str(), err);
+            *resp.body_mut() = data.into();
+            return;
+        }
+    };
+
+    let headers = resp.headers_mut();
+
+    headers.insert(
+        header::CONTENT_ENCODING,
+        HeaderValue::from_static(encoding.as_str()),
+    );
+    headers.remove(header::CONTENT_LENGTH);
+
+    if let Some(etag) = headers
+        .get(header::ETAG)
+        .and_then(|x| x.to_str().ok())
+        .and_then(|etag| encoding.tag_etag(etag))
+    {
+        headers.insert(header::ETAG, HeaderValue::from_str(&etag).unwrap());
+    }
+
+    *resp.body_mut() = compressed.into();
+}
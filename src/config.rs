@@ -1,4 +1,4 @@
-use std::{env, fmt::Debug, str::FromStr};
+use std::{env, fmt::Debug, str::FromStr, time::Duration};
 
 use sai::{Component, ComponentLifecycle};
 
@@ -12,18 +12,100 @@ where
     var.parse().expect("Please set dotenv to valid value")
 }
 
+/// 설정하지 않았으면 기본값을 사용함
+fn env_or<T>(key: &str, default: T) -> T
+where
+    T: FromStr,
+    <T as FromStr>::Err: Debug,
+{
+    match env::var(key) {
+        Ok(var) => var.parse().expect("Please set dotenv to valid value"),
+        Err(_) => default,
+    }
+}
+
+/// `LIBRARY_STORAGE=postgres|memory|sqlite`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LibraryStorage {
+    Postgres,
+    /// 데이터베이스 없이 실행함, 종료하면 데이터가 사라짐
+    Memory,
+    /// 서버 하나로 실행하거나 개발할 때 사용함
+    Sqlite,
+}
+
+impl FromStr for LibraryStorage {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "postgres" => Ok(Self::Postgres),
+            "memory" => Ok(Self::Memory),
+            "sqlite" => Ok(Self::Sqlite),
+            _ => Err(format!("unknown library storage `{s}`")),
+        }
+    }
+}
+
+/// postgresql 연결 풀 설정, replica도 같은 설정을 사용함
+#[derive(Debug, Clone)]
+pub struct PostgresPoolConfig {
+    pub max_connections: u32,
+    pub min_connections: u32,
+    /// 연결 하나를 맺을 때까지 기다리는 시간
+    pub connect_timeout: Duration,
+    /// 사용하지 않는 연결을 닫을 때까지의 시간
+    pub idle_timeout: Duration,
+    /// 풀에서 연결을 받을 때까지 기다리는 시간
+    pub acquire_timeout: Duration,
+    /// 0이면 제한하지 않음
+    pub statement_timeout: Duration,
+    /// 시작할 때 연결에 실패하면 다시 시도하는 횟수
+    pub connect_retries: u32,
+    /// 0이면 풀 상태를 기록하지 않음
+    pub stats_interval: Duration,
+}
+
 #[derive(Debug, Component)]
 #[lifecycle]
 pub struct Config {
     port: Option<u16>,
 
+    library_storage: Option<LibraryStorage>,
+
     postgres_url: Option<String>,
+    postgres_pool: Option<PostgresPoolConfig>,
+    /// (host, url)
+    postgres_replica_urls: Option<Vec<(String, String)>>,
+    postgres_replica_health_check_interval: Option<u64>,
+    postgres_replica_read_after_write: Option<u64>,
+    sqlite_url: Option<String>,
+    /// 시작할 때 남아있는 마이그레이션을 적용함
+    auto_migrate: Option<bool>,
     /* postgres_port: Option<String>,
     postgres_host: Option<String>,
     postgres_user: Option<String>,
     postgres_pw: Option<String>,
     postgres_db: Option<String>, */
     madome_auth_url: Option<String>,
+
+    books_lookup_limit: Option<usize>,
+
+    /// 0이면 캐시하지 않음
+    book_cache_size: Option<usize>,
+    book_cache_ttl: Option<u64>,
+    /// 같은 인자로 동시에 들어온 작품 읽기를 쿼리 하나로 합침
+    single_flight: Option<bool>,
+
+    /// `GET /books/:book_id`
+    book_cache_control: Option<String>,
+    /// `GET /books`, `GET /books/facets`, `GET /:directory/:name/books`
+    books_cache_control: Option<String>,
+
+    /// `Accept-Encoding`에 따라 gzip이나 brotli로 압축함
+    compression: Option<bool>,
+    /// 이보다 작은 본문은 압축하지 않음, 바이트 단위
+    compression_min_size: Option<usize>,
 }
 
 #[async_trait::async_trait]
@@ -33,19 +115,107 @@ impl ComponentLifecycle for Config {
 
         self.port.replace(env("PORT"));
 
-        let pg_port: u16 = env("POSTGRES_PORT");
-        let pg_host: String = env("POSTGRES_HOST");
-        let pg_user: String = env("POSTGRES_USER");
-        let pg_pw: String = env("POSTGRES_PW");
-        let pg_db: String = env("POSTGRES_DB");
-        let pg_url = format!(
-            "postgres://{}:{}@{}:{}/{}",
-            pg_user, pg_pw, pg_host, pg_port, pg_db
-        );
-        self.postgres_url.replace(pg_url);
+        let library_storage = env_or("LIBRARY_STORAGE", LibraryStorage::Postgres);
+        self.library_storage.replace(library_storage);
+
+        // 다른 저장소를 사용하면 postgresql 설정은 필요없음
+        if library_storage == LibraryStorage::Postgres {
+            let pg_port: u16 = env("POSTGRES_PORT");
+            let pg_host: String = env("POSTGRES_HOST");
+            let pg_user: String = env("POSTGRES_USER");
+            let pg_pw: String = env("POSTGRES_PW");
+            let pg_db: String = env("POSTGRES_DB");
+            let pg_url = format!(
+                "postgres://{}:{}@{}:{}/{}",
+                pg_user, pg_pw, pg_host, pg_port, pg_db
+            );
+            self.postgres_url.replace(pg_url);
+
+            self.postgres_pool.replace(PostgresPoolConfig {
+                max_connections: env_or("POSTGRES_MAX_CONNECTIONS", 10),
+                min_connections: env_or("POSTGRES_MIN_CONNECTIONS", 1),
+                connect_timeout: Duration::from_secs(env_or("POSTGRES_CONNECT_TIMEOUT", 5)),
+                idle_timeout: Duration::from_secs(env_or("POSTGRES_IDLE_TIMEOUT", 600)),
+                acquire_timeout: Duration::from_secs(env_or("POSTGRES_ACQUIRE_TIMEOUT", 30)),
+                statement_timeout: Duration::from_millis(env_or(
+                    "POSTGRES_STATEMENT_TIMEOUT_MS",
+                    30_000,
+                )),
+                connect_retries: env_or("POSTGRES_CONNECT_RETRIES", 5),
+                stats_interval: Duration::from_secs(env_or("POSTGRES_POOL_STATS_INTERVAL", 60)),
+            });
+
+            // `POSTGRES_REPLICA_HOSTS=replica-0:5432,replica-1`, 나머지는 primary 설정을 그대로 사용함
+            let replica_hosts: String = env_or("POSTGRES_REPLICA_HOSTS", String::new());
+            let replica_user: String = env_or("POSTGRES_REPLICA_USER", pg_user);
+            let replica_pw: String = env_or("POSTGRES_REPLICA_PW", pg_pw);
+            let replica_urls = replica_hosts
+                .split(',')
+                .map(str::trim)
+                .filter(|host| !host.is_empty())
+                .map(|host| {
+                    let host = if host.contains(':') {
+                        host.to_string()
+                    } else {
+                        format!("{}:{}", host, pg_port)
+                    };
+                    let url = format!(
+                        "postgres://{}:{}@{}/{}",
+                        replica_user, replica_pw, host, pg_db
+                    );
+                    (host, url)
+                })
+                .collect();
+            self.postgres_replica_urls.replace(replica_urls);
+
+            // `tokio::time::interval`은 0이면 panic함
+            let health_check_interval = env_or("POSTGRES_REPLICA_HEALTH_CHECK_INTERVAL", 5);
+            assert!(
+                health_check_interval > 0,
+                "POSTGRES_REPLICA_HEALTH_CHECK_INTERVAL must be greater than 0"
+            );
+            self.postgres_replica_health_check_interval
+                .replace(health_check_interval);
+
+            // replica에 반영되는 시간보다 길어야 함, 0이면 쓴 직후에도 replica에서 읽음
+            self.postgres_replica_read_after_write
+                .replace(env_or("POSTGRES_REPLICA_READ_AFTER_WRITE_MS", 1000));
+        }
+
+        if library_storage == LibraryStorage::Sqlite {
+            // 파일이 없으면 만듦
+            self.sqlite_url.replace(env_or(
+                "SQLITE_URL",
+                "sqlite://library.sqlite?mode=rwc".to_string(),
+            ));
+        }
+
+        self.auto_migrate.replace(env_or("AUTO_MIGRATE", false));
 
         self.madome_auth_url.replace(env("MADOME_AUTH_URL"));
 
+        self.books_lookup_limit
+            .replace(env_or("BOOKS_LOOKUP_LIMIT", 1000));
+
+        self.book_cache_size
+            .replace(env_or("BOOK_CACHE_SIZE", 10000));
+        self.book_cache_ttl.replace(env_or("BOOK_CACHE_TTL", 60));
+        self.single_flight.replace(env_or("SINGLE_FLIGHT", true));
+
+        // 외부 요청은 토큰을 확인해야 해서, `public`으로 바꾸면 CDN이 인증 없이 응답할 수 있음
+        self.book_cache_control.replace(env_or(
+            "BOOK_CACHE_CONTROL",
+            "private, no-cache".to_string(),
+        ));
+        self.books_cache_control.replace(env_or(
+            "BOOKS_CACHE_CONTROL",
+            "private, no-cache".to_string(),
+        ));
+
+        self.compression.replace(env_or("COMPRESSION", true));
+        self.compression_min_size
+            .replace(env_or("COMPRESSION_MIN_SIZE", 1024));
+
         log::info!("{:?}", self);
     }
 }
@@ -55,11 +225,107 @@ impl Config {
         self.port.unwrap()
     }
 
+    pub fn library_storage(&self) -> LibraryStorage {
+        self.library_storage.unwrap()
+    }
+
     pub fn postgres_url(&self) -> &str {
         self.postgres_url.as_ref().unwrap()
     }
 
+    pub fn postgres_pool(&self) -> &PostgresPoolConfig {
+        self.postgres_pool.as_ref().unwrap()
+    }
+
+    pub fn postgres_replica_urls(&self) -> &[(String, String)] {
+        self.postgres_replica_urls.as_deref().unwrap_or_default()
+    }
+
+    /// 초 단위
+    pub fn postgres_replica_health_check_interval(&self) -> Duration {
+        Duration::from_secs(self.postgres_replica_health_check_interval.unwrap())
+    }
+
+    /// 작품을 쓴 뒤에 읽기도 primary를 사용하는 시간
+    pub fn postgres_replica_read_after_write(&self) -> Duration {
+        Duration::from_millis(self.postgres_replica_read_after_write.unwrap())
+    }
+
+    pub fn sqlite_url(&self) -> &str {
+        self.sqlite_url.as_ref().unwrap()
+    }
+
+    pub fn auto_migrate(&self) -> bool {
+        self.auto_migrate.unwrap()
+    }
+
     pub fn auth_url(&self) -> &str {
         self.madome_auth_url.as_ref().unwrap()
     }
+
+    /// `POST /books/lookup`에서 한번에 받을 수 있는 id의 최대 갯수
+    pub fn books_lookup_limit(&self) -> usize {
+        self.books_lookup_limit.unwrap()
+    }
+
+    /// 캐시할 작품의 최대 갯수, 0이면 캐시하지 않음
+    pub fn book_cache_size(&self) -> usize {
+        self.book_cache_size.unwrap()
+    }
+
+    /// 초 단위
+    pub fn book_cache_ttl(&self) -> Duration {
+        Duration::from_secs(self.book_cache_ttl.unwrap())
+    }
+
+    pub fn single_flight(&self) -> bool {
+        self.single_flight.unwrap()
+    }
+
+    /// 작품 하나를 응답할 때의 `Cache-Control`
+    pub fn book_cache_control(&self) -> &str {
+        self.book_cache_control.as_ref().unwrap()
+    }
+
+    /// 작품 목록을 응답할 때의 `Cache-Control`
+    pub fn books_cache_control(&self) -> &str {
+        self.books_cache_control.as_ref().unwrap()
+    }
+
+    pub fn compression(&self) -> bool {
+        self.compression.unwrap()
+    }
+
+    /// 바이트 단위
+    pub fn compression_min_size(&self) -> usize {
+        self.compression_min_size.unwrap()
+    }
+}
+
+#[cfg(test)]
+impl Config {
+    /// 환경변수 없이 기본값으로 만듦, 데이터베이스 설정은 비어있음
+    pub fn for_test() -> Self {
+        Self {
+            port: None,
+            library_storage: Some(LibraryStorage::Memory),
+            postgres_url: None,
+            postgres_pool: None,
+            postgres_replica_urls: None,
+            postgres_replica_health_check_interval: None,
+            postgres_replica_read_after_write: None,
+            sqlite_url: None,
+            auto_migrate: Some(false),
+            madome_auth_url: None,
+            books_lookup_limit: Some(1000),
+            book_cache_size: Some(10000),
+            book_cache_ttl: Some(60),
+            single_flight: Some(true),
+            // 경로마다 다른 값이 쓰이는지 구분하기 위해 기본값과 다르게 둠
+            book_cache_control: Some("private, max-age=60".to_string()),
+            books_cache_control: Some("private, max-age=10".to_string()),
+            compression: Some(true),
+            compression_min_size: Some(1024),
+        }
+    }
 }
@@ -0,0 +1,2 @@
+// UNIQUE constraint failed: books.id
+pub const UNIQUE_CONSTRAINT_FAILED: &str = "UNIQUE constraint failed";
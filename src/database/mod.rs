@@ -1,9 +1,24 @@
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use itertools::Itertools;
+use migration::{Migrator, MigratorTrait};
+use parking_lot::Mutex;
 use sai::{Component, ComponentLifecycle, Injected};
-use sea_orm::{ConnectOptions, Database, DatabaseConnection};
+use sea_orm::{
+    sqlx::postgres::PgPool, ConnectOptions, Database, DatabaseConnection, SqlxPostgresConnector,
+};
+use tokio::task::JoinHandle;
+
+use crate::config::{Config, LibraryStorage, PostgresPoolConfig};
 
-use crate::config::Config;
+use postgresql::pool::{self, PoolStats};
+use replica::ReplicaSet;
 
 pub mod postgresql;
+mod replica;
 
 #[derive(Component)]
 #[lifecycle]
@@ -12,29 +27,213 @@ pub struct DatabaseSet {
     config: Injected<Config>,
 
     postgresql: Option<DatabaseConnection>,
+
+    postgresql_replicas: Arc<ReplicaSet>,
+
+    /// 이 시각까지는 읽기도 primary를 사용함
+    primary_pinned_until: Mutex<Option<Instant>>,
+
+    replica_health_check: Option<JoinHandle<()>>,
+
+    pool_stats_log: Option<JoinHandle<()>>,
+
+    sqlite: Option<DatabaseConnection>,
 }
 
 #[async_trait::async_trait]
 impl ComponentLifecycle for DatabaseSet {
     async fn start(&mut self) {
-        let postgresql = Self::connect_postgresql(self.config.postgres_url()).await;
+        // 사용하지 않는 데이터베이스에는 연결하지 않음
+        match self.config.library_storage() {
+            LibraryStorage::Postgres => {
+                let pool_config = self.config.postgres_pool();
+
+                let pool = Self::connect_postgresql(self.config.postgres_url(), pool_config).await;
+                let postgresql = SqlxPostgresConnector::from_sqlx_postgres_pool(pool.clone());
+
+                Self::migrate_postgresql(
+                    &postgresql,
+                    self.config.postgres_url(),
+                    pool_config,
+                    self.config.auto_migrate(),
+                )
+                .await;
+
+                self.postgresql.replace(postgresql);
+
+                let replicas =
+                    ReplicaSet::connect(self.config.postgres_replica_urls(), pool_config).await;
+
+                if !replicas.is_empty() {
+                    let replicas = Arc::new(replicas);
+                    let interval = self.config.postgres_replica_health_check_interval();
 
-        self.postgresql.replace(postgresql);
+                    self.replica_health_check
+                        .replace(tokio::spawn(Self::check_replicas(
+                            Arc::clone(&replicas),
+                            interval,
+                        )));
+
+                    self.postgresql_replicas = replicas;
+                }
+
+                if !pool_config.stats_interval.is_zero() {
+                    self.pool_stats_log
+                        .replace(tokio::spawn(Self::log_pool_stats(
+                            pool,
+                            Arc::clone(&self.postgresql_replicas),
+                            pool_config.clone(),
+                        )));
+                }
+            }
+            LibraryStorage::Sqlite => {
+                let sqlite = Self::connect_sqlite(self.config.sqlite_url()).await;
+
+                Self::migrate(&sqlite, self.config.auto_migrate()).await;
+
+                self.sqlite.replace(sqlite);
+            }
+            LibraryStorage::Memory => {}
+        }
     }
 
     async fn stop(&mut self) {
+        for task in [self.replica_health_check.take(), self.pool_stats_log.take()]
+            .into_iter()
+            .flatten()
+        {
+            task.abort();
+        }
+
         // log::info!("disconnect to database");
     }
 }
 
 impl DatabaseSet {
-    async fn connect_postgresql(url: &str) -> DatabaseConnection {
+    async fn connect_postgresql(url: &str, config: &PostgresPoolConfig) -> PgPool {
+        pool::connect_with_retry(url, config).await
+    }
+
+    async fn connect_sqlite(url: &str) -> DatabaseConnection {
         let option = ConnectOptions::new(url.to_string());
 
-        Database::connect(option).await.expect("connect postgresql")
+        Database::connect(option).await.expect("connect sqlite")
+    }
+
+    /// 응답하지 않는 replica는 다음 health check에 성공할 때까지 읽기에서 제외함
+    async fn check_replicas(replicas: Arc<ReplicaSet>, interval: Duration) {
+        let mut interval = tokio::time::interval(interval);
+
+        loop {
+            interval.tick().await;
+
+            replicas.check().await;
+        }
+    }
+
+    async fn log_pool_stats(
+        primary: PgPool,
+        replicas: Arc<ReplicaSet>,
+        config: PostgresPoolConfig,
+    ) {
+        let mut interval = tokio::time::interval(config.stats_interval);
+
+        loop {
+            interval.tick().await;
+
+            log::info!(
+                "postgresql pool: {}",
+                PoolStats::of(&primary, config.max_connections)
+            );
+
+            for (host, stats) in replicas.pool_stats(config.max_connections) {
+                log::info!("postgresql replica pool {}: {}", host, stats);
+            }
+        }
+    }
+
+    /// 오래 걸리는 마이그레이션이 `statement_timeout`에 취소되지 않게 timeout이 없는 연결로 적용함
+    async fn migrate_postgresql(
+        db: &DatabaseConnection,
+        url: &str,
+        config: &PostgresPoolConfig,
+        auto_migrate: bool,
+    ) {
+        if !auto_migrate {
+            return Self::migrate(db, false).await;
+        }
+
+        let config = PostgresPoolConfig {
+            statement_timeout: Duration::ZERO,
+            ..config.clone()
+        };
+        let pool = Self::connect_postgresql(url, &config).await;
+
+        Self::migrate(
+            &SqlxPostgresConnector::from_sqlx_postgres_pool(pool.clone()),
+            true,
+        )
+        .await;
+
+        pool.close().await;
+    }
+
+    /// 스키마는 `migration` crate에서만 바꿈
+    ///
+    /// 적용하지 않은 마이그레이션이 남아있으면 서버를 시작하지 않음
+    async fn migrate(db: &DatabaseConnection, auto_migrate: bool) {
+        if auto_migrate {
+            Migrator::up(db, None).await.expect("apply migrations");
+        }
+
+        let pending = Migrator::get_pending_migrations(db)
+            .await
+            .expect("get pending migrations");
+
+        if !pending.is_empty() {
+            panic!(
+                "pending migrations: {}; run `migration up` or set AUTO_MIGRATE=true",
+                pending.iter().map(|x| x.name()).join(", ")
+            );
+        }
     }
 
+    /// 쓰기와 방금 쓴 데이터를 읽어야 하는 요청은 primary를 사용함
     pub fn postgresql(&self) -> &DatabaseConnection {
         self.postgresql.as_ref().unwrap()
     }
+
+    /// 읽기 전용 요청에 사용함
+    ///
+    /// replica가 없거나 모두 제외됐으면, 또는 `pin_primary` 이후 잠깐 동안은 primary를 사용함
+    pub fn postgresql_read(&self) -> &DatabaseConnection {
+        if self.is_primary_pinned() {
+            return self.postgresql();
+        }
+
+        self.postgresql_replicas
+            .next()
+            .unwrap_or_else(|| self.postgresql())
+    }
+
+    /// primary에 쓴 뒤에 호출함
+    ///
+    /// replica에 반영되기 전에 다시 읽어도 방금 쓴 데이터가 보이도록 `POSTGRES_REPLICA_READ_AFTER_WRITE_MS` 동안 읽기도 primary를 사용함
+    pub fn pin_primary(&self) {
+        if self.postgresql_replicas.is_empty() {
+            return;
+        }
+
+        let until = Instant::now() + self.config.postgres_replica_read_after_write();
+
+        self.primary_pinned_until.lock().replace(until);
+    }
+
+    fn is_primary_pinned(&self) -> bool {
+        matches!(*self.primary_pinned_until.lock(), Some(until) if Instant::now() < until)
+    }
+
+    pub fn sqlite(&self) -> &DatabaseConnection {
+        self.sqlite.as_ref().unwrap()
+    }
 }
@@ -1,14 +1,24 @@
 use itertools::Itertools;
-use sea_orm::{
-    prelude::*,
-    sea_query::{ColumnDef, Table},
-    ConnectionTrait,
-};
+use sea_orm::prelude::*;
 
 use crate::entity::Book;
 
 use super::book_tag;
 
+/// 작품마다 미리 정해둔 `[0, 1)` 범위의 난수
+///
+/// 무작위 정렬에만 사용하고 insert할 때는 데이터베이스가 채우기 때문에 Model에는 없음
+///
+/// `migration`의 `m20220520_000001_add_books_random_key` 참조
+pub const RANDOM_KEY: &str = "random_key";
+
+/// 작품에 붙은 태그들의 id, postgresql에만 있음
+///
+/// `"books_tag_ref"`가 바뀌면 trigger가 채우기 때문에 Model에는 없음
+///
+/// `migration`의 `m20220710_000001_add_books_tag_ids` 참조
+pub const TAG_IDS: &str = "tag_ids";
+
 #[derive(Debug, Clone, PartialEq, DeriveEntityModel)]
 #[sea_orm(table_name = "books")]
 pub struct Model {
@@ -87,35 +97,9 @@ impl From<(Model, Vec<book_tag::Model>)> for Book {
     }
 }
 
-pub async fn create_table(db: &DatabaseConnection) {
-    let stmt = Table::create()
-        .table(Entity)
-        .if_not_exists()
-        .col(ColumnDef::new(Column::Id).big_integer().primary_key())
-        .col(ColumnDef::new(Column::Title).string().not_null())
-        .col(ColumnDef::new(Column::Kind).string().not_null())
-        .col(ColumnDef::new(Column::Page).integer().not_null())
-        .col(ColumnDef::new(Column::Language).string().not_null())
-        .col(
-            ColumnDef::new(Column::CreatedAt)
-                .timestamp_with_time_zone()
-                .not_null(),
-        )
-        .to_owned();
-
-    let builder = db.get_database_backend();
-    db.execute(builder.build(&stmt))
-        .await
-        .expect("create table entity::book");
-}
-
 #[allow(clippy::enum_variant_names)]
 pub mod tag_ref {
-    use sea_orm::{
-        prelude::*,
-        sea_query::{ColumnDef, ForeignKey, ForeignKeyAction, Index, Table},
-        ConnectionTrait, Statement,
-    };
+    use sea_orm::prelude::*;
 
     use crate::database::postgresql::entity;
 
@@ -170,84 +154,4 @@ pub mod tag_ref {
             }
         }
     }
-
-    pub async fn create_table(db: &DatabaseConnection) {
-        let stmt = Table::create()
-            .table(Entity)
-            .if_not_exists()
-            .col(
-                ColumnDef::new(Column::Id)
-                    .big_integer()
-                    .auto_increment()
-                    .primary_key(),
-            )
-            .col(ColumnDef::new(Column::BookId).big_integer().not_null())
-            .col(ColumnDef::new(Column::BookTagId).big_integer().not_null())
-            .foreign_key(
-                ForeignKey::create()
-                    .name(Column::BookId.as_str())
-                    .from(Entity, Column::BookId)
-                    .to(entity::book::Entity, entity::book::Column::Id)
-                    .on_delete(ForeignKeyAction::Cascade),
-            )
-            .foreign_key(
-                ForeignKey::create()
-                    .name(Column::BookTagId.as_str())
-                    .from(Entity, Column::BookTagId)
-                    .to(entity::book_tag::Entity, entity::book_tag::Column::Id)
-                    .on_delete(ForeignKeyAction::Cascade),
-            )
-            .to_owned();
-
-        let builder = db.get_database_backend();
-        db.execute(builder.build(&stmt))
-            .await
-            .expect("create table entity::book::tag_ref");
-
-        // TODO: https://github.com/SeaQL/sea-query/issues/232
-        // sea-orm create index issue
-
-        /* let idx_book_id = Index::create()
-        .table(Entity)
-        .name("idx-book-id")
-        .col(Column::BookId)
-        .to_owned(); */
-        let idx_book_id = Statement::from_string(
-            builder,
-            format!(
-                r#"
-                CREATE INDEX IF NOT EXISTS "idx-book-id"
-                    ON "{}" ("{}")
-                "#,
-                Entity.as_str(),
-                Column::BookId.as_str()
-            ),
-        );
-
-        db.execute(idx_book_id)
-            .await
-            .expect("create index entity::book::tag_ref idx-book-id");
-
-        /* let idx_book_tag_id = Index::create()
-        .table(Entity)
-        .name("idx-book-tag-id")
-        .col(Column::BookTagId)
-        .to_owned(); */
-
-        let idx_book_tag_id = Statement::from_string(
-            builder,
-            format!(
-                r#"
-                CREATE INDEX IF NOT EXISTS "idx-book-tag-id"
-                    ON "{}" ("{}")
-                "#,
-                Entity.as_str(),
-                Column::BookTagId.as_str()
-            ),
-        );
-
-        db.execute(idx_book_tag_id)
-            .await
-            .expect("create index entity::book::tag_ref idx-book-tag-id");
-    }
 }
@@ -1,8 +1,4 @@
-use sea_orm::{
-    prelude::*,
-    sea_query::{ColumnDef, Index, Table},
-    ConnectionTrait, Statement,
-};
+use sea_orm::prelude::*;
 
 use crate::entity::BookTag;
 
@@ -35,51 +31,9 @@ impl From<BookTag> for ActiveModel {
 
 impl From<Model> for BookTag {
     fn from(Model { kind, name, .. }: Model) -> Self {
-        match kind.as_str() {
-            "artist" => Self::Artist(name),
-            "series" => Self::Series(name),
-            "group" => Self::Group(name),
-            "character" => Self::Character(name),
-            "female" => Self::Female(name),
-            "male" => Self::Male(name),
-            "misc" => Self::Misc(name),
-            _ => unreachable!(),
+        match Self::new(&kind, name) {
+            Some(tag) => tag,
+            None => unreachable!(),
         }
     }
 }
-
-pub async fn create_table(db: &DatabaseConnection) {
-    let stmt = Table::create()
-        .table(Entity)
-        .if_not_exists()
-        .col(
-            ColumnDef::new(Column::Id)
-                .big_integer()
-                .primary_key()
-                .auto_increment(),
-        )
-        .col(ColumnDef::new(Column::Kind).string().not_null())
-        .col(ColumnDef::new(Column::Name).string().not_null())
-        .to_owned();
-
-    let builder = db.get_database_backend();
-    db.execute(builder.build(&stmt))
-        .await
-        .expect("create table entity::book_tag");
-
-    let idx_name = Statement::from_string(
-        builder,
-        format!(
-            r#"
-            CREATE INDEX IF NOT EXISTS "idx-name"
-                ON "{}" ("{}")
-            "#,
-            Entity.as_str(),
-            Column::Name.as_str()
-        ),
-    );
-
-    db.execute(idx_name)
-        .await
-        .expect("create index entity::book_tag idx-name");
-}
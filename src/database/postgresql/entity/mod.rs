@@ -1,2 +1,4 @@
 pub mod book;
 pub mod book_tag;
+pub mod saved_search;
+pub mod tag_stat;
@@ -0,0 +1,72 @@
+use sea_orm::prelude::*;
+
+use crate::entity::SavedSearch;
+
+#[derive(Debug, Clone, PartialEq, DeriveEntityModel)]
+#[sea_orm(table_name = "saved_searches")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i64,
+    pub user_id: Uuid,
+    pub name: String,
+    /// `payload::BookFilter`의 JSON
+    pub filter: String,
+    pub last_seen_max_id: i64,
+    pub created_at: DateTimeUtc,
+    pub updated_at: DateTimeUtc,
+}
+
+#[derive(Debug, Clone, Copy, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl From<SavedSearch> for ActiveModel {
+    fn from(
+        SavedSearch {
+            user_id,
+            name,
+            filter,
+            last_seen_max_id,
+            created_at,
+            updated_at,
+            ..
+        }: SavedSearch,
+    ) -> Self {
+        use sea_orm::ActiveValue::*;
+
+        Self {
+            id: NotSet,
+            user_id: Set(user_id),
+            name: Set(name),
+            filter: Set(filter),
+            last_seen_max_id: Set(last_seen_max_id as i64),
+            created_at: Set(created_at),
+            updated_at: Set(updated_at),
+        }
+    }
+}
+
+impl From<Model> for SavedSearch {
+    fn from(
+        Model {
+            id,
+            user_id,
+            name,
+            filter,
+            last_seen_max_id,
+            created_at,
+            updated_at,
+        }: Model,
+    ) -> Self {
+        Self {
+            id: id as u64,
+            user_id,
+            name,
+            filter,
+            last_seen_max_id: last_seen_max_id as u32,
+            created_at,
+            updated_at,
+        }
+    }
+}
@@ -0,0 +1,22 @@
+use sea_orm::prelude::*;
+
+/// 태그마다 작품 종류와 언어별 작품 수
+///
+/// 작품을 추가할 때 같이 갱신함, `repository::sql::insert_book` 참조
+#[derive(Debug, Clone, PartialEq, DeriveEntityModel)]
+#[sea_orm(table_name = "tag_stats")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub book_tag_id: i64,
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub book_kind: String,
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub language: String,
+    pub book_count: i64,
+    pub latest_created_at: DateTimeUtc,
+}
+
+#[derive(Debug, Clone, Copy, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
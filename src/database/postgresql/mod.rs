@@ -1 +1,3 @@
 pub mod entity;
+pub mod notify;
+pub mod pool;
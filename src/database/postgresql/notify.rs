@@ -0,0 +1,32 @@
+use itertools::Itertools;
+use sea_orm::{ConnectionTrait, DbErr, Statement};
+
+/// 작품이 바뀌면 바뀐 작품들의 id를 `,`로 이어서 보냄
+pub const LIBRARY_CHANGES: &str = "library_changes";
+
+/// 트랜잭션 안에서 호출하면 커밋될 때 보내짐
+pub async fn notify_library_changes(
+    txn: &impl ConnectionTrait,
+    book_ids: &[u32],
+) -> Result<(), DbErr> {
+    let payload = book_ids.iter().join(",");
+
+    let stmt = Statement::from_sql_and_values(
+        txn.get_database_backend(),
+        "SELECT pg_notify($1, $2)",
+        [LIBRARY_CHANGES.into(), payload.into()],
+    );
+
+    txn.execute(stmt).await?;
+
+    Ok(())
+}
+
+/// 잘못된 id가 있으면 None
+pub fn parse_library_changes(payload: &str) -> Option<Vec<u32>> {
+    payload
+        .split(',')
+        .filter(|x| !x.is_empty())
+        .map(|x| x.parse().ok())
+        .collect()
+}
@@ -0,0 +1,114 @@
+use std::{fmt, time::Duration};
+
+use sea_orm::sqlx::{
+    postgres::{PgConnectOptions, PgPool, PgPoolOptions},
+    Executor,
+};
+
+use crate::config::PostgresPoolConfig;
+
+/// 연결을 다시 시도할 때 기다리는 최대 시간
+pub const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+pub async fn connect(url: &str, config: &PostgresPoolConfig) -> Result<PgPool, String> {
+    let options: PgConnectOptions = url.parse().map_err(|err| format!("{}", err))?;
+
+    let pool = pool_options(config).connect_with(options);
+
+    match tokio::time::timeout(config.connect_timeout, pool).await {
+        Ok(Ok(pool)) => Ok(pool),
+        Ok(Err(err)) => Err(format!("{}", err)),
+        Err(_) => Err("connect timeout".to_string()),
+    }
+}
+
+/// 처음 사용할 때 연결함, 시작할 때 응답하지 않던 replica를 나중에 다시 사용하기 위함
+pub fn connect_lazy(url: &str, config: &PostgresPoolConfig) -> Result<PgPool, String> {
+    let options: PgConnectOptions = url.parse().map_err(|err| format!("{}", err))?;
+
+    Ok(pool_options(config).connect_lazy_with(options))
+}
+
+fn pool_options(config: &PostgresPoolConfig) -> PgPoolOptions {
+    let statement_timeout = config.statement_timeout.as_millis();
+
+    PgPoolOptions::new()
+        .max_connections(config.max_connections)
+        .min_connections(config.min_connections)
+        // sqlx 0.5의 connect_timeout은 풀에서 연결을 받을 때까지의 시간임
+        .connect_timeout(config.acquire_timeout)
+        .idle_timeout(config.idle_timeout)
+        .after_connect(move |conn| {
+            Box::pin(async move {
+                if statement_timeout > 0 {
+                    conn.execute(format!("SET statement_timeout = {}", statement_timeout).as_str())
+                        .await?;
+                }
+                Ok(())
+            })
+        })
+}
+
+/// 데이터베이스가 잠깐 준비되지 않았을 수 있어서 간격을 두 배씩 늘리며 다시 시도함
+pub async fn connect_with_retry(url: &str, config: &PostgresPoolConfig) -> PgPool {
+    let mut backoff = Duration::from_secs(1);
+    let mut attempt = 0;
+
+    loop {
+        match connect(url, config).await {
+            Ok(pool) => return pool,
+            Err(err) if attempt < config.connect_retries => {
+                attempt += 1;
+
+                log::warn!(
+                    "connect postgresql: {}; retry {}/{} after {:?}",
+                    err,
+                    attempt,
+                    config.connect_retries,
+                    backoff
+                );
+
+                tokio::time::sleep(backoff).await;
+
+                backoff = (backoff * 2).min(MAX_BACKOFF);
+            }
+            Err(err) => panic!("connect postgresql: {}", err),
+        }
+    }
+}
+
+/// 풀 크기를 정할 때 참고함
+#[derive(Debug, Clone, Copy)]
+pub struct PoolStats {
+    /// 열려있는 연결, 사용 중인 연결 포함
+    pub size: u32,
+    pub idle: usize,
+    pub max: u32,
+}
+
+impl PoolStats {
+    pub fn of(pool: &PgPool, max: u32) -> Self {
+        Self {
+            size: pool.size(),
+            idle: pool.num_idle(),
+            max,
+        }
+    }
+
+    pub fn in_use(&self) -> usize {
+        (self.size as usize).saturating_sub(self.idle)
+    }
+}
+
+impl fmt::Display for PoolStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "size={} idle={} in_use={} max={}",
+            self.size,
+            self.idle,
+            self.in_use(),
+            self.max
+        )
+    }
+}
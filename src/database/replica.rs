@@ -0,0 +1,119 @@
+use std::{
+    sync::atomic::{AtomicBool, AtomicUsize, Ordering},
+    time::Duration,
+};
+
+use sea_orm::{
+    sqlx::postgres::PgPool, ConnectionTrait, DatabaseConnection, SqlxPostgresConnector, Statement,
+};
+
+use crate::config::PostgresPoolConfig;
+
+use super::postgresql::pool::{self, PoolStats};
+
+/// health check에서 `SELECT 1`을 기다리는 최대 시간
+const CHECK_TIMEOUT: Duration = Duration::from_secs(1);
+
+struct Replica {
+    /// 로그에 비밀번호가 남지 않게 url 대신 사용함
+    host: String,
+    pool: PgPool,
+    connection: DatabaseConnection,
+    healthy: AtomicBool,
+}
+
+/// 읽기 전용 postgresql replica
+///
+/// 정상인 replica를 차례대로 돌아가며 사용하고, health check에 실패한 replica는 다시 성공할 때까지 제외함
+#[derive(Default)]
+pub struct ReplicaSet {
+    replicas: Vec<Replica>,
+    next: AtomicUsize,
+}
+
+impl ReplicaSet {
+    /// 연결하지 못한 replica는 제외된 상태로 시작하고, health check에 성공하면 사용함
+    pub async fn connect(urls: &[(String, String)], config: &PostgresPoolConfig) -> Self {
+        let mut replicas = Vec::with_capacity(urls.len());
+
+        for (host, url) in urls {
+            let (pool, healthy) = match pool::connect(url, config).await {
+                Ok(pool) => (pool, true),
+                Err(err) => {
+                    log::warn!("connect postgresql replica {}: {}", host, err);
+
+                    match pool::connect_lazy(url, config) {
+                        Ok(pool) => (pool, false),
+                        Err(err) => {
+                            log::warn!("invalid postgresql replica {}: {}", host, err);
+                            continue;
+                        }
+                    }
+                }
+            };
+
+            replicas.push(Replica {
+                host: host.to_string(),
+                connection: SqlxPostgresConnector::from_sqlx_postgres_pool(pool.clone()),
+                pool,
+                healthy: AtomicBool::new(healthy),
+            });
+        }
+
+        Self {
+            replicas,
+            next: AtomicUsize::new(0),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.replicas.is_empty()
+    }
+
+    /// 정상인 replica가 없으면 None
+    pub fn next(&self) -> Option<&DatabaseConnection> {
+        let len = self.replicas.len();
+
+        if len == 0 {
+            return None;
+        }
+
+        let start = self.next.fetch_add(1, Ordering::Relaxed);
+
+        (0..len)
+            .map(|i| &self.replicas[(start + i) % len])
+            .find(|replica| replica.healthy.load(Ordering::Relaxed))
+            .map(|replica| &replica.connection)
+    }
+
+    /// (host, stats)
+    pub fn pool_stats(&self, max: u32) -> Vec<(&str, PoolStats)> {
+        self.replicas
+            .iter()
+            .map(|replica| (replica.host.as_str(), PoolStats::of(&replica.pool, max)))
+            .collect()
+    }
+
+    /// 모든 replica를 동시에 확인함, 응답하지 않는 replica 하나가 다른 replica의 확인을 늦추지 않게 하기 위함
+    pub async fn check(&self) {
+        let checks = self.replicas.iter().map(|replica| async move {
+            let db = &replica.connection;
+            let stmt = Statement::from_string(db.get_database_backend(), "SELECT 1".to_string());
+
+            let healthy = matches!(
+                tokio::time::timeout(CHECK_TIMEOUT, db.execute(stmt)).await,
+                Ok(Ok(_))
+            );
+
+            let was_healthy = replica.healthy.swap(healthy, Ordering::Relaxed);
+
+            if was_healthy && !healthy {
+                log::warn!("eject postgresql replica {}", replica.host);
+            } else if !was_healthy && healthy {
+                log::info!("restore postgresql replica {}", replica.host);
+            }
+        });
+
+        futures::future::join_all(checks).await;
+    }
+}
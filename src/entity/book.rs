@@ -4,7 +4,7 @@ use chrono::{DateTime, Utc};
 
 use super::{BookTag, Sort};
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Book {
     pub id: u32,
     pub title: String,
@@ -15,7 +15,7 @@ pub struct Book {
     pub created_at: DateTime<Utc>,
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum BookKind {
     Manga,
     Doujinshi,
@@ -46,9 +46,63 @@ impl From<String> for BookKind {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum BookSortBy {
     Id(Sort),
-    Random,
+    /// seed가 같으면 페이지가 달라도 같은 순서를 유지함
+    Random(u32),
+}
+
+/// 비어있는 필드는 조건에서 제외됨
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct BookFilter {
+    pub kinds: Vec<BookKind>,
+    pub languages: Vec<String>,
+    pub min_page: Option<usize>,
+    pub max_page: Option<usize>,
+    pub created_after: Option<DateTime<Utc>>,
+    pub created_before: Option<DateTime<Utc>>,
+    /// 이 id보다 나중에 추가된 작품만, 저장된 검색의 새 작품 수를 셀 때 사용함
+    pub after_id: Option<u32>,
+    /// 모든 태그가 붙은 작품만
+    pub tags: Vec<BookTag>,
+    /// 하나라도 붙은 작품은 제외함
+    pub exclude_tags: Vec<BookTag>,
+}
+
+impl BookFilter {
+    pub fn is_empty(&self) -> bool {
+        let Self {
+            kinds,
+            languages,
+            min_page,
+            max_page,
+            created_after,
+            created_before,
+            after_id,
+            tags,
+            exclude_tags,
+        } = self;
+
+        kinds.is_empty()
+            && languages.is_empty()
+            && min_page.is_none()
+            && max_page.is_none()
+            && created_after.is_none()
+            && created_before.is_none()
+            && after_id.is_none()
+            && tags.is_empty()
+            && exclude_tags.is_empty()
+    }
+}
+
+/// 필터에 맞는 작품들을 종류별로 센 결과
+#[derive(Debug, Default)]
+pub struct BookFacets {
+    pub kinds: Vec<(BookKind, usize)>,
+    pub languages: Vec<(String, usize)>,
+    /// 태그 종류마다 작품 수가 많은 순서대로 상위 N개
+    pub tags: Vec<(BookTag, usize)>,
 }
 
 ///
@@ -72,6 +126,6 @@ pub enum BookSortBy {
 /// ]
 /// ```
 pub struct BookGroupByTag {
-    tag: BookTag,
-    books: Vec<Book>,
+    pub tag: BookTag,
+    pub books: Vec<Book>,
 }
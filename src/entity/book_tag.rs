@@ -1,4 +1,8 @@
-#[derive(Debug, Clone)]
+use chrono::{DateTime, Utc};
+
+use super::{BookKind, Sort};
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum BookTag {
     Artist(String),
     Series(String),
@@ -10,6 +14,26 @@ pub enum BookTag {
 }
 
 impl BookTag {
+    /// 모르는 kind라면 None
+    pub fn new(kind: &str, name: impl Into<String>) -> Option<Self> {
+        use BookTag::*;
+
+        let name = name.into();
+
+        let tag = match kind {
+            "artist" => Artist(name),
+            "series" => Series(name),
+            "group" => Group(name),
+            "character" => Character(name),
+            "female" => Female(name),
+            "male" => Male(name),
+            "misc" => Misc(name),
+            _ => return None,
+        };
+
+        Some(tag)
+    }
+
     pub fn kind(&self) -> &str {
         use BookTag::*;
 
@@ -38,3 +62,59 @@ impl BookTag {
         }
     }
 }
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BookTagKind {
+    Artist,
+    Series,
+    Group,
+    Character,
+    Female,
+    Male,
+    Misc,
+}
+
+impl BookTagKind {
+    pub fn as_str(&self) -> &str {
+        use BookTagKind::*;
+
+        match self {
+            Artist => "artist",
+            Series => "series",
+            Group => "group",
+            Character => "character",
+            Female => "female",
+            Male => "male",
+            Misc => "misc",
+        }
+    }
+
+    pub fn tag(self, name: impl Into<String>) -> BookTag {
+        BookTag::new(self.as_str(), name).unwrap()
+    }
+}
+
+/// 태그 종류별 목록에서 태그 하나에 대한 요약
+#[derive(Debug)]
+pub struct BookTagSummary {
+    pub tag: BookTag,
+    pub book_count: usize,
+    /// 이 태그가 붙은 작품 중에 가장 최근에 추가된 작품의 시각
+    pub latest_created_at: DateTime<Utc>,
+}
+
+pub enum BookTagSortBy {
+    BookCount(Sort),
+    Name(Sort),
+    LatestCreatedAt(Sort),
+}
+
+/// 미리 세어둔 태그 통계와 다시 센 값이 다른 항목
+#[derive(Debug)]
+pub struct BookTagStatDrift {
+    pub tag: BookTag,
+    pub book_kind: BookKind,
+    pub language: String,
+    pub stored: usize,
+    pub actual: usize,
+}
@@ -1,10 +1,12 @@
 mod book;
 mod book_tag;
+mod saved_search;
 
 pub use book::*;
 pub use book_tag::*;
+pub use saved_search::*;
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Sort {
     Desc,
     Asc,
@@ -0,0 +1,17 @@
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+#[derive(Debug, Clone)]
+pub struct SavedSearch {
+    pub id: u64,
+    pub user_id: Uuid,
+    pub name: String,
+    /// `payload::BookFilter`를 JSON으로 직렬화한 값
+    ///
+    /// 실행할 때마다 다시 검사하기 때문에 검사를 통과한 뒤의 값(`BookFilter`)은 저장하지 않음
+    pub filter: String,
+    /// 마지막으로 이 검색의 작품 목록을 봤을 때 가장 최근 작품의 id
+    pub last_seen_max_id: u32,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
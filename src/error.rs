@@ -7,7 +7,12 @@ use crate::{
     config::Config,
     model::Presenter,
     payload,
-    usecase::{get_book, get_books, get_books_by_ids},
+    usecase::{
+        create_saved_search, delete_saved_search, get_book, get_book_cache_stats, get_book_facets,
+        get_book_tags, get_books, get_books_by_ids, get_random_book, get_saved_search,
+        get_saved_search_books, get_saved_searches, lookup_books, recompute_tag_stats,
+        update_saved_search,
+    },
 };
 
 #[derive(Debug, thiserror::Error)]
@@ -52,6 +57,10 @@ type MsgError = crate::msg::Error;
 pub enum RepositoryError {
     #[error("SeaOrm: {0}")]
     SeaOrm(#[from] sea_orm::DbErr),
+
+    /// 같은 쿼리를 기다리던 요청들이 먼저 시작한 요청의 에러를 같이 받음
+    #[error("SingleFlight: {0}")]
+    SingleFlight(Arc<crate::Error>),
 }
 
 impl From<sea_orm::DbErr> for crate::Error {
@@ -83,6 +92,42 @@ pub enum UseCaseError {
     #[error("GetBooksByIds: {0}")]
     GetBooksByIds(#[from] get_books_by_ids::Error),
 
+    #[error("GetRandomBook: {0}")]
+    GetRandomBook(#[from] get_random_book::Error),
+
+    #[error("GetBookFacets: {0}")]
+    GetBookFacets(#[from] get_book_facets::Error),
+
+    #[error("GetBookTags: {0}")]
+    GetBookTags(#[from] get_book_tags::Error),
+
+    #[error("LookupBooks: {0}")]
+    LookupBooks(#[from] lookup_books::Error),
+
+    #[error("GetSavedSearches: {0}")]
+    GetSavedSearches(#[from] get_saved_searches::Error),
+
+    #[error("GetSavedSearch: {0}")]
+    GetSavedSearch(#[from] get_saved_search::Error),
+
+    #[error("CreateSavedSearch: {0}")]
+    CreateSavedSearch(#[from] create_saved_search::Error),
+
+    #[error("UpdateSavedSearch: {0}")]
+    UpdateSavedSearch(#[from] update_saved_search::Error),
+
+    #[error("DeleteSavedSearch: {0}")]
+    DeleteSavedSearch(#[from] delete_saved_search::Error),
+
+    #[error("GetSavedSearchBooks: {0}")]
+    GetSavedSearchBooks(#[from] get_saved_search_books::Error),
+
+    #[error("RecomputeTagStats: {0}")]
+    RecomputeTagStats(#[from] recompute_tag_stats::Error),
+
+    #[error("GetBookCacheStats: {0}")]
+    GetBookCacheStats(#[from] get_book_cache_stats::Error),
+
     #[error("CreateBook: ")]
     CreateBook,
 }
@@ -106,6 +151,21 @@ impl Presenter for Error {
                 resp.set_body("Not found".into());
             }
 
+            Msg(err @ Unauthorized) => {
+                resp.set_status(StatusCode::UNAUTHORIZED).unwrap();
+                resp.set_body(err.to_string().into());
+            }
+
+            Msg(err @ Forbidden) => {
+                resp.set_status(StatusCode::FORBIDDEN).unwrap();
+                resp.set_body(err.to_string().into());
+            }
+
+            Payload(err @ payload::Error::TooLargeBody(_)) => {
+                resp.set_status(StatusCode::PAYLOAD_TOO_LARGE).unwrap();
+                resp.set_body(err.to_string().into());
+            }
+
             Payload(err) => {
                 resp.set_status(StatusCode::BAD_REQUEST).unwrap();
                 resp.set_body(err.to_string().into());
@@ -116,6 +176,40 @@ impl Presenter for Error {
                 resp.set_body(err.to_string().into());
             }
 
+            UseCase(GetRandomBook(err @ get_random_book::Error::NotFoundBook)) => {
+                resp.set_status(StatusCode::NOT_FOUND).unwrap();
+                resp.set_body(err.to_string().into());
+            }
+
+            UseCase(GetSavedSearch(err @ get_saved_search::Error::NotFoundSavedSearch)) => {
+                resp.set_status(StatusCode::NOT_FOUND).unwrap();
+                resp.set_body(err.to_string().into());
+            }
+
+            UseCase(UpdateSavedSearch(err @ update_saved_search::Error::NotFoundSavedSearch)) => {
+                resp.set_status(StatusCode::NOT_FOUND).unwrap();
+                resp.set_body(err.to_string().into());
+            }
+
+            UseCase(DeleteSavedSearch(err @ delete_saved_search::Error::NotFoundSavedSearch)) => {
+                resp.set_status(StatusCode::NOT_FOUND).unwrap();
+                resp.set_body(err.to_string().into());
+            }
+
+            UseCase(GetSavedSearchBooks(
+                err @ get_saved_search_books::Error::NotFoundSavedSearch,
+            )) => {
+                resp.set_status(StatusCode::NOT_FOUND).unwrap();
+                resp.set_body(err.to_string().into());
+            }
+
+            UseCase(CreateSavedSearch(
+                err @ create_saved_search::Error::TooManySavedSearches(_),
+            )) => {
+                resp.set_status(StatusCode::CONFLICT).unwrap();
+                resp.set_body(err.to_string().into());
+            }
+
             AuthSdk(ref err) => {
                 use madome_sdk::api::{auth::Error as AuthError, BaseError};
 
@@ -1,5 +1,6 @@
 mod app;
 mod command;
+mod compression;
 mod config;
 mod constant;
 mod database;
@@ -1,42 +1,130 @@
-use std::sync::Arc;
+use std::{str::FromStr, sync::Arc};
 
 use chrono::{DateTime, Utc};
-use hyper::{header, Body, Request, Response, StatusCode};
+use hyper::{
+    header::{self, HeaderName},
+    Body, Request, Response, Uri,
+};
+use itertools::Itertools;
 use serde::Serialize;
 use util::{elapse, http::SetResponse};
 
 use crate::{config::Config, entity};
 
-use super::Presenter;
+use super::{http_cache, Presenter};
 
+/// `fields=`로 제외된 필드는 None이고 응답에서 빠짐
 #[derive(Debug, Serialize)]
 pub struct Book {
     pub id: u32,
-    pub title: String,
-    pub kind: String,
-    pub page: usize,
-    pub language: String,
-    pub tags: Vec<(String, String)>,
-    pub created_at: DateTime<Utc>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub kind: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub page: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tags: Option<Vec<(String, String)>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+impl Book {
+    pub fn project(self, fields: BookFields) -> Self {
+        let BookFields {
+            title,
+            kind,
+            page,
+            language,
+            tags,
+            created_at,
+        } = fields;
+
+        Self {
+            id: self.id,
+            title: self.title.filter(|_| title),
+            kind: self.kind.filter(|_| kind),
+            page: self.page.filter(|_| page),
+            language: self.language.filter(|_| language),
+            tags: self.tags.filter(|_| tags),
+            created_at: self.created_at.filter(|_| created_at),
+        }
+    }
+}
+
+/// 응답에 포함할 `Book`의 필드, `id`는 항상 포함됨
+///
+/// `fields=id,title,kind`
+#[derive(Debug, Clone, Copy)]
+pub struct BookFields {
+    pub title: bool,
+    pub kind: bool,
+    pub page: bool,
+    pub language: bool,
+    pub tags: bool,
+    pub created_at: bool,
+}
+
+impl Default for BookFields {
+    fn default() -> Self {
+        Self {
+            title: true,
+            kind: true,
+            page: true,
+            language: true,
+            tags: true,
+            created_at: true,
+        }
+    }
+}
+
+impl FromStr for BookFields {
+    /// 알 수 없는 필드 이름
+    type Err = String;
+
+    fn from_str(fields: &str) -> Result<Self, Self::Err> {
+        let mut r = Self {
+            title: false,
+            kind: false,
+            page: false,
+            language: false,
+            tags: false,
+            created_at: false,
+        };
+
+        for field in fields.split(',').map(str::trim).filter(|x| !x.is_empty()) {
+            match field {
+                "id" => {}
+                "title" => r.title = true,
+                "kind" => r.kind = true,
+                "page" => r.page = true,
+                "language" => r.language = true,
+                "tags" => r.tags = true,
+                "created_at" => r.created_at = true,
+                _ => return Err(field.to_owned()),
+            }
+        }
+
+        Ok(r)
+    }
 }
 
 #[async_trait::async_trait]
 impl Presenter for Book {
     async fn set_response(
         self,
-        _request: &mut Request<Body>,
+        request: &mut Request<Body>,
         resp: &mut Response<Body>,
-        _config: Arc<Config>,
+        config: Arc<Config>,
     ) -> crate::Result<()> {
         let serialized = elapse!(
             "serialize",
             serde_json::to_vec(&self).expect("json serialize")
         );
 
-        resp.set_status(StatusCode::OK).unwrap();
-        resp.set_header(header::CONTENT_TYPE, "application/json")
-            .unwrap();
-        resp.set_body(serialized.into());
+        http_cache::set_json_body(request, resp, &config, serialized);
 
         Ok(())
     }
@@ -46,24 +134,151 @@ impl Presenter for Book {
 impl Presenter for Vec<Book> {
     async fn set_response(
         self,
-        _request: &mut Request<Body>,
+        request: &mut Request<Body>,
         resp: &mut Response<Body>,
-        _config: Arc<Config>,
+        config: Arc<Config>,
     ) -> crate::Result<()> {
         let serialized = elapse!(
             "serialize",
             serde_json::to_vec(&self).expect("json serialize")
         );
 
-        resp.set_status(StatusCode::OK).unwrap();
-        resp.set_header(header::CONTENT_TYPE, "application/json")
+        http_cache::set_json_body(request, resp, &config, serialized);
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum Total {
+    Exact(usize),
+    Estimated(usize),
+}
+
+impl Total {
+    pub fn count(&self) -> usize {
+        match self {
+            Self::Exact(x) | Self::Estimated(x) => *x,
+        }
+    }
+
+    pub fn is_estimated(&self) -> bool {
+        matches!(self, Self::Estimated(_))
+    }
+}
+
+/// `total`이 없으면 기존처럼 작품 배열만 응답함
+#[derive(Debug)]
+pub struct BookPage {
+    pub books: Vec<Book>,
+    pub total: Option<Total>,
+    pub page: usize,
+    pub per_page: usize,
+    /// `sort-by=random`일 때 사용한 seed, seed를 보내지 않았어도 다음 페이지가 같은 순서를 유지하도록 응답함
+    ///
+    /// `total`과 상관없이 `X-Random-Seed`로 응답하고, `total`이 있으면 링크에도 포함함
+    pub seed: Option<u32>,
+}
+
+#[derive(Serialize)]
+struct BookPageEnvelope<'a> {
+    books: &'a [Book],
+    total: usize,
+    total_estimated: bool,
+    total_pages: usize,
+    page: usize,
+    per_page: usize,
+}
+
+#[async_trait::async_trait]
+impl Presenter for BookPage {
+    async fn set_response(
+        self,
+        request: &mut Request<Body>,
+        resp: &mut Response<Body>,
+        config: Arc<Config>,
+    ) -> crate::Result<()> {
+        if let Some(seed) = self.seed {
+            resp.set_header(HeaderName::from_static("x-random-seed"), seed)
+                .unwrap();
+        }
+
+        let total = match self.total {
+            Some(total) => total,
+            None => return self.books.set_response(request, resp, config).await,
+        };
+
+        let total_pages = last_page(total.count(), self.per_page);
+
+        let serialized = elapse!(
+            "serialize",
+            serde_json::to_vec(&BookPageEnvelope {
+                books: &self.books,
+                total: total.count(),
+                total_estimated: total.is_estimated(),
+                total_pages,
+                page: self.page,
+                per_page: self.per_page,
+            })
+            .expect("json serialize")
+        );
+
+        resp.set_header(HeaderName::from_static("x-total-count"), total.count())
             .unwrap();
-        resp.set_body(serialized.into());
+        resp.set_header(
+            header::LINK,
+            link_header(request.uri(), self.page, total_pages, self.seed),
+        )
+        .unwrap();
+
+        http_cache::set_json_body(request, resp, &config, serialized);
 
         Ok(())
     }
 }
 
+/// 결과가 없어도 첫번째 페이지는 있는 것으로 취급함
+fn last_page(total: usize, per_page: usize) -> usize {
+    ((total + per_page - 1) / per_page).max(1)
+}
+
+/// RFC 8288
+///
+/// `</books?kind[]=manga&page=1>; rel="first", </books?kind[]=manga&page=4>; rel="next", ...`
+fn link_header(uri: &Uri, page: usize, last_page: usize, seed: Option<u32>) -> String {
+    let path = uri.path();
+    let seed = seed.map(|seed| format!("seed={seed}"));
+    let rest = uri
+        .query()
+        .unwrap_or_default()
+        .split('&')
+        .filter(|x| !x.is_empty() && *x != "page" && !x.starts_with("page="))
+        .filter(|x| seed.is_none() || (*x != "seed" && !x.starts_with("seed=")))
+        .chain(seed.as_deref())
+        .collect::<Vec<_>>();
+
+    let link = |page: usize, rel: &str| {
+        let page = format!("page={page}");
+        let query = rest.iter().copied().chain([page.as_str()]).join("&");
+
+        format!(r#"<{path}?{query}>; rel="{rel}""#)
+    };
+
+    let mut links = vec![link(1, "first")];
+
+    if page > 1 {
+        links.push(link((page - 1).min(last_page), "prev"));
+    }
+
+    if page < last_page {
+        links.push(link(page + 1, "next"));
+    }
+
+    links.push(link(last_page, "last"));
+
+    links.join(", ")
+}
+
 impl From<entity::Book> for Book {
     fn from(
         entity::Book {
@@ -83,12 +298,12 @@ impl From<entity::Book> for Book {
 
         Self {
             id,
-            title,
-            kind: kind.as_str().to_owned(),
-            page,
-            language,
-            tags,
-            created_at,
+            title: Some(title),
+            kind: Some(kind.as_str().to_owned()),
+            page: Some(page),
+            language: Some(language),
+            tags: Some(tags),
+            created_at: Some(created_at),
         }
     }
 }
@@ -0,0 +1,58 @@
+use std::sync::Arc;
+
+use hyper::{header, Body, Request, Response, StatusCode};
+use serde::Serialize;
+use util::{elapse, http::SetResponse};
+
+use crate::{config::Config, repository::CacheStats};
+
+use super::Presenter;
+
+/// 서버가 시작된 뒤로 누적된 값
+#[derive(Debug, Default, Serialize)]
+pub struct BookCacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub len: usize,
+    pub capacity: usize,
+}
+
+impl From<CacheStats> for BookCacheStats {
+    fn from(
+        CacheStats {
+            hits,
+            misses,
+            len,
+            capacity,
+        }: CacheStats,
+    ) -> Self {
+        Self {
+            hits,
+            misses,
+            len,
+            capacity,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl Presenter for BookCacheStats {
+    async fn set_response(
+        self,
+        _request: &mut Request<Body>,
+        resp: &mut Response<Body>,
+        _config: Arc<Config>,
+    ) -> crate::Result<()> {
+        let serialized = elapse!(
+            "serialize",
+            serde_json::to_vec(&self).expect("json serialize")
+        );
+
+        resp.set_status(StatusCode::OK).unwrap();
+        resp.set_header(header::CONTENT_TYPE, "application/json")
+            .unwrap();
+        resp.set_body(serialized.into());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,92 @@
+use std::{collections::BTreeMap, sync::Arc};
+
+use hyper::{Body, Request, Response};
+use serde::Serialize;
+use util::elapse;
+
+use crate::{config::Config, entity};
+
+use super::{http_cache, Presenter};
+
+#[derive(Debug, Serialize)]
+pub struct FacetBucket {
+    pub value: String,
+    pub count: usize,
+}
+
+/// ```json
+/// {
+///     "kind": [{ "value": "doujinshi", "count": 120 }],
+///     "language": [{ "value": "korean", "count": 98 }],
+///     "tags": {
+///         "artist": [{ "value": "..", "count": 7 }],
+///         "female": [{ "value": "..", "count": 30 }]
+///     }
+/// }
+/// ```
+#[derive(Debug, Serialize)]
+pub struct BookFacets {
+    pub kind: Vec<FacetBucket>,
+    pub language: Vec<FacetBucket>,
+    pub tags: BTreeMap<String, Vec<FacetBucket>>,
+}
+
+#[async_trait::async_trait]
+impl Presenter for BookFacets {
+    async fn set_response(
+        self,
+        request: &mut Request<Body>,
+        resp: &mut Response<Body>,
+        config: Arc<Config>,
+    ) -> crate::Result<()> {
+        let serialized = elapse!(
+            "serialize",
+            serde_json::to_vec(&self).expect("json serialize")
+        );
+
+        http_cache::set_json_body(request, resp, &config, serialized);
+
+        Ok(())
+    }
+}
+
+impl From<entity::BookFacets> for BookFacets {
+    fn from(
+        entity::BookFacets {
+            kinds,
+            languages,
+            tags,
+        }: entity::BookFacets,
+    ) -> Self {
+        let kind = kinds
+            .into_iter()
+            .map(|(kind, count)| FacetBucket {
+                value: kind.as_str().to_owned(),
+                count,
+            })
+            .collect();
+
+        let language = languages
+            .into_iter()
+            .map(|(value, count)| FacetBucket { value, count })
+            .collect();
+
+        let tags = tags
+            .into_iter()
+            .fold(BTreeMap::new(), |mut acc, (tag, count)| {
+                acc.entry(tag.kind().to_owned())
+                    .or_insert_with(Vec::new)
+                    .push(FacetBucket {
+                        value: tag.name().to_owned(),
+                        count,
+                    });
+                acc
+            });
+
+        Self {
+            kind,
+            language,
+            tags,
+        }
+    }
+}
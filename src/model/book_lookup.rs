@@ -0,0 +1,35 @@
+use std::sync::Arc;
+
+use hyper::{Body, Request, Response};
+use serde::Serialize;
+use util::elapse;
+
+use crate::config::Config;
+
+use super::{http_cache, Book, Presenter};
+
+/// `books`는 요청한 id의 순서를 따르고, 없는 작품의 id는 `missing_ids`에 있음
+#[derive(Debug, Serialize)]
+pub struct BookLookup {
+    pub books: Vec<Book>,
+    pub missing_ids: Vec<u32>,
+}
+
+#[async_trait::async_trait]
+impl Presenter for BookLookup {
+    async fn set_response(
+        self,
+        request: &mut Request<Body>,
+        resp: &mut Response<Body>,
+        config: Arc<Config>,
+    ) -> crate::Result<()> {
+        let serialized = elapse!(
+            "serialize",
+            serde_json::to_vec(&self).expect("json serialize")
+        );
+
+        http_cache::set_json_body(request, resp, &config, serialized);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,55 @@
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use hyper::{header, Body, Request, Response, StatusCode};
+use serde::Serialize;
+use util::{elapse, http::SetResponse};
+
+use crate::{config::Config, entity};
+
+use super::Presenter;
+
+#[derive(Debug, Serialize)]
+pub struct BookTagSummary {
+    pub name: String,
+    pub book_count: usize,
+    pub latest_created_at: DateTime<Utc>,
+}
+
+impl From<entity::BookTagSummary> for BookTagSummary {
+    fn from(
+        entity::BookTagSummary {
+            tag,
+            book_count,
+            latest_created_at,
+        }: entity::BookTagSummary,
+    ) -> Self {
+        Self {
+            name: tag.name().to_owned(),
+            book_count,
+            latest_created_at,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl Presenter for Vec<BookTagSummary> {
+    async fn set_response(
+        self,
+        _request: &mut Request<Body>,
+        resp: &mut Response<Body>,
+        _config: Arc<Config>,
+    ) -> crate::Result<()> {
+        let serialized = elapse!(
+            "serialize",
+            serde_json::to_vec(&self).expect("json serialize")
+        );
+
+        resp.set_status(StatusCode::OK).unwrap();
+        resp.set_header(header::CONTENT_TYPE, "application/json")
+            .unwrap();
+        resp.set_body(serialized.into());
+
+        Ok(())
+    }
+}
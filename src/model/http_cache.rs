@@ -0,0 +1,179 @@
+use hyper::{header, Body, Method, Request, Response, StatusCode};
+use util::http::SetResponse;
+
+use crate::{
+    compression::{self, Encoding},
+    config::Config,
+};
+
+/// 직렬화한 내용으로 만든 strong ETag와 경로에 맞는 `Cache-Control`을 붙임
+///
+/// `If-None-Match`에 같은 ETag가 있으면 본문 없이 304로 응답함
+pub fn set_json_body(
+    request: &Request<Body>,
+    resp: &mut Response<Body>,
+    config: &Config,
+    serialized: Vec<u8>,
+) {
+    let etag = etag_of(&serialized);
+
+    resp.set_header(header::CONTENT_TYPE, "application/json")
+        .unwrap();
+    resp.set_header(
+        header::CACHE_CONTROL,
+        cache_control(request.uri().path(), config),
+    )
+    .unwrap();
+
+    if is_not_modified(request, &etag) {
+        // 압축해서 응답했을 때와 같은 ETag를 보내야 함
+        let accept_encoding = request
+            .headers()
+            .get(header::ACCEPT_ENCODING)
+            .and_then(|x| x.to_str().ok());
+        let etag = compression::encoding_of(accept_encoding, serialized.len(), config)
+            .and_then(|encoding| encoding.tag_etag(&etag))
+            .unwrap_or(etag);
+
+        resp.set_header(header::ETAG, etag.as_str()).unwrap();
+        resp.set_status(StatusCode::NOT_MODIFIED).unwrap();
+        return;
+    }
+
+    resp.set_header(header::ETAG, etag.as_str()).unwrap();
+    resp.set_status(StatusCode::OK).unwrap();
+    resp.set_body(serialized.into());
+}
+
+/// `/books/random`은 매번 달라야 하고, `/me/*`는 사용자마다 다르기 때문에 설정과 상관없이 정해져 있음
+///
+/// `POST /books/lookup`은 요청 본문에 따라 응답이 달라서 캐시하지 않음
+fn cache_control<'a>(path: &str, config: &'a Config) -> &'a str {
+    if path == "/books/random" || path == "/books/lookup" {
+        "no-store"
+    } else if path.starts_with("/me/") {
+        "private, no-cache"
+    } else if path == "/books" || path == "/books/facets" || path.ends_with("/books") {
+        config.books_cache_control()
+    } else {
+        config.book_cache_control()
+    }
+}
+
+/// 서버가 다시 시작되거나 인스턴스가 달라도 같은 내용이면 같은 값이 나와야 해서 FNV-1a를 사용함
+fn etag_of(body: &[u8]) -> String {
+    let hash = body.iter().fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+        (hash ^ *byte as u64).wrapping_mul(0x0000_0100_0000_01b3)
+    });
+
+    format!(r#""{:x}-{:016x}""#, body.len(), hash)
+}
+
+/// RFC 7232, `If-None-Match`는 weak comparison을 사용함
+///
+/// 압축한 응답의 ETag에 붙인 인코딩은 떼고 비교함
+fn is_not_modified(request: &Request<Body>, etag: &str) -> bool {
+    if !matches!(*request.method(), Method::GET | Method::HEAD) {
+        return false;
+    }
+
+    let if_none_match = match request
+        .headers()
+        .get(header::IF_NONE_MATCH)
+        .and_then(|x| x.to_str().ok())
+    {
+        Some(x) => x,
+        None => return false,
+    };
+
+    if_none_match
+        .split(',')
+        .map(str::trim)
+        .any(|x| x == "*" || Encoding::untag_etag(x.trim_start_matches("W/")) == etag)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `Accept-Encoding`과 `If-None-Match`를 보낸 `GET /books/1`의 응답, `app::service`처럼 압축까지 함
+    async fn respond(
+        accept_encoding: &str,
+        if_none_match: Option<&str>,
+        serialized: Vec<u8>,
+    ) -> Response<Body> {
+        let config = Config::for_test();
+
+        let mut request = Request::builder()
+            .uri("/books/1")
+            .header(header::ACCEPT_ENCODING, accept_encoding);
+        if let Some(etag) = if_none_match {
+            request = request.header(header::IF_NONE_MATCH, etag);
+        }
+        let request = request.body(Body::empty()).unwrap();
+
+        let mut resp = Response::new(Body::empty());
+
+        set_json_body(&request, &mut resp, &config, serialized);
+        compression::compress(Some(accept_encoding), &mut resp, &config).await;
+
+        resp
+    }
+
+    fn etag(resp: &Response<Body>) -> &str {
+        resp.headers()[header::ETAG].to_str().unwrap()
+    }
+
+    #[tokio::test]
+    async fn not_modified_repeats_etag_of_compressed_response() {
+        let serialized = serde_json::to_vec(&vec!["book"; 500]).unwrap();
+
+        let ok = respond("br", None, serialized.clone()).await;
+
+        assert_eq!(ok.status(), StatusCode::OK);
+        assert_eq!(ok.headers()[header::CONTENT_ENCODING], "br");
+        assert!(etag(&ok).ends_with("-br\""));
+
+        let not_modified = respond("br", Some(etag(&ok)), serialized).await;
+
+        assert_eq!(not_modified.status(), StatusCode::NOT_MODIFIED);
+        assert_eq!(etag(&not_modified), etag(&ok));
+        assert_eq!(not_modified.headers()[header::VARY], "accept-encoding");
+    }
+
+    #[tokio::test]
+    async fn not_modified_repeats_etag_of_uncompressed_response() {
+        // `COMPRESSION_MIN_SIZE`보다 작아서 압축하지 않음
+        let serialized = serde_json::to_vec(&vec!["book"; 3]).unwrap();
+
+        let ok = respond("br", None, serialized.clone()).await;
+
+        assert_eq!(ok.status(), StatusCode::OK);
+        assert!(!ok.headers().contains_key(header::CONTENT_ENCODING));
+
+        let not_modified = respond("br", Some(etag(&ok)), serialized).await;
+
+        assert_eq!(not_modified.status(), StatusCode::NOT_MODIFIED);
+        assert_eq!(etag(&not_modified), etag(&ok));
+    }
+
+    #[test]
+    fn cache_control_follows_route() {
+        let config = Config::for_test();
+
+        assert_eq!(
+            cache_control("/books/facets", &config),
+            config.books_cache_control()
+        );
+        assert_eq!(
+            cache_control("/artists/abc/books", &config),
+            config.books_cache_control()
+        );
+        assert_eq!(
+            cache_control("/books/1", &config),
+            config.book_cache_control()
+        );
+        assert_eq!(cache_control("/books/lookup", &config), "no-store");
+        assert_eq!(cache_control("/books/random", &config), "no-store");
+    }
+}
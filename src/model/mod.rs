@@ -1,6 +1,19 @@
 mod book;
+mod book_cache_stats;
+mod book_facets;
+mod book_lookup;
+mod book_tag;
+mod http_cache;
+mod saved_search;
+mod tag_stats;
 
-pub use book::Book;
+pub use book::{Book, BookFields, BookPage, Total};
+pub use book_cache_stats::BookCacheStats;
+pub use book_facets::BookFacets;
+pub use book_lookup::BookLookup;
+pub use book_tag::BookTagSummary;
+pub use saved_search::SavedSearch;
+pub use tag_stats::TagStatsReport;
 
 use std::sync::Arc;
 
@@ -9,7 +22,19 @@ use util::http::SetResponse;
 
 use crate::{config::Config, into_model, model};
 
-into_model![(Book, Book), (Books, Vec<Book>)];
+into_model![
+    (Book, Book),
+    (Books, Vec<Book>),
+    (BookPage, BookPage),
+    (BookFacets, BookFacets),
+    (BookTagSummaries, Vec<BookTagSummary>),
+    (BookLookup, BookLookup),
+    (SavedSearch, SavedSearch),
+    (SavedSearches, Vec<SavedSearch>),
+    (TagStatsReport, TagStatsReport),
+    (BookCacheStats, BookCacheStats),
+    (NoContent, ()),
+];
 
 #[async_trait::async_trait]
 pub trait Presenter: Sized {
@@ -22,6 +47,21 @@ pub trait Presenter: Sized {
     ) -> crate::Result<()>;
 }
 
+/// 응답할 내용이 없음
+#[async_trait::async_trait]
+impl Presenter for () {
+    async fn set_response(
+        self,
+        _request: &mut Request<Body>,
+        resp: &mut Response<Body>,
+        _config: Arc<Config>,
+    ) -> crate::Result<()> {
+        resp.set_status(StatusCode::NO_CONTENT).unwrap();
+
+        Ok(())
+    }
+}
+
 #[macro_export]
 macro_rules! into_model {
     () => {
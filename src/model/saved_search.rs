@@ -0,0 +1,93 @@
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use hyper::{header, Body, Request, Response, StatusCode};
+use serde::Serialize;
+use util::{elapse, http::SetResponse};
+
+use crate::{config::Config, entity};
+
+use super::Presenter;
+
+#[derive(Debug, Serialize)]
+pub struct SavedSearch {
+    pub id: u64,
+    pub name: String,
+    /// `GET /books`와 같은 형태의 필터
+    pub filter: serde_json::Value,
+    pub last_seen_max_id: u32,
+    /// 마지막으로 본 뒤에 새로 추가된 작품 수
+    pub new_count: usize,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl SavedSearch {
+    pub fn new(
+        entity::SavedSearch {
+            id,
+            name,
+            filter,
+            last_seen_max_id,
+            created_at,
+            updated_at,
+            ..
+        }: entity::SavedSearch,
+        new_count: usize,
+    ) -> Self {
+        Self {
+            id,
+            name,
+            // 저장할 때 직렬화한 값이기 때문에 실패하지 않음
+            filter: serde_json::from_str(&filter).unwrap_or_default(),
+            last_seen_max_id,
+            new_count,
+            created_at,
+            updated_at,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl Presenter for SavedSearch {
+    async fn set_response(
+        self,
+        _request: &mut Request<Body>,
+        resp: &mut Response<Body>,
+        _config: Arc<Config>,
+    ) -> crate::Result<()> {
+        let serialized = elapse!(
+            "serialize",
+            serde_json::to_vec(&self).expect("json serialize")
+        );
+
+        resp.set_status(StatusCode::OK).unwrap();
+        resp.set_header(header::CONTENT_TYPE, "application/json")
+            .unwrap();
+        resp.set_body(serialized.into());
+
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl Presenter for Vec<SavedSearch> {
+    async fn set_response(
+        self,
+        _request: &mut Request<Body>,
+        resp: &mut Response<Body>,
+        _config: Arc<Config>,
+    ) -> crate::Result<()> {
+        let serialized = elapse!(
+            "serialize",
+            serde_json::to_vec(&self).expect("json serialize")
+        );
+
+        resp.set_status(StatusCode::OK).unwrap();
+        resp.set_header(header::CONTENT_TYPE, "application/json")
+            .unwrap();
+        resp.set_body(serialized.into());
+
+        Ok(())
+    }
+}
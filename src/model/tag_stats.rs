@@ -0,0 +1,68 @@
+use std::sync::Arc;
+
+use hyper::{header, Body, Request, Response, StatusCode};
+use serde::Serialize;
+use util::{elapse, http::SetResponse};
+
+use crate::{config::Config, entity};
+
+use super::Presenter;
+
+/// 태그 통계를 다시 센 결과, 어긋나 있던 항목이 없으면 `drifts`는 비어있음
+#[derive(Debug, Serialize)]
+pub struct TagStatsReport {
+    pub drifts: Vec<TagStatDrift>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TagStatDrift {
+    pub tag_kind: String,
+    pub tag_name: String,
+    pub book_kind: String,
+    pub language: String,
+    pub stored: usize,
+    pub actual: usize,
+}
+
+impl From<entity::BookTagStatDrift> for TagStatDrift {
+    fn from(
+        entity::BookTagStatDrift {
+            tag,
+            book_kind,
+            language,
+            stored,
+            actual,
+        }: entity::BookTagStatDrift,
+    ) -> Self {
+        Self {
+            tag_kind: tag.kind().to_owned(),
+            tag_name: tag.name().to_owned(),
+            book_kind: book_kind.as_str().to_owned(),
+            language,
+            stored,
+            actual,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl Presenter for TagStatsReport {
+    async fn set_response(
+        self,
+        _request: &mut Request<Body>,
+        resp: &mut Response<Body>,
+        _config: Arc<Config>,
+    ) -> crate::Result<()> {
+        let serialized = elapse!(
+            "serialize",
+            serde_json::to_vec(&self).expect("json serialize")
+        );
+
+        resp.set_status(StatusCode::OK).unwrap();
+        resp.set_header(header::CONTENT_TYPE, "application/json")
+            .unwrap();
+        resp.set_body(serialized.into());
+
+        Ok(())
+    }
+}
@@ -4,6 +4,7 @@ use hyper::{header, Body, Method, Request, Response};
 
 use madome_sdk::api::auth;
 use parking_lot::RwLock;
+use percent_encoding::percent_decode_str;
 use serde::Deserialize;
 use util::{
     elapse,
@@ -15,14 +16,26 @@ use util::{
 
 use crate::{
     config::Config,
+    entity::{BookTag, BookTagKind},
     payload,
-    usecase::{get_book, get_books, get_books_by_ids},
+    usecase::{
+        create_saved_search, delete_saved_search, get_book, get_book_cache_stats, get_book_facets,
+        get_book_tags, get_books, get_books_by_ids, get_random_book, get_saved_search,
+        get_saved_search_books, get_saved_searches, lookup_books, recompute_tag_stats,
+        update_saved_search,
+    },
 };
 
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
     #[error("Not found")]
     NotFound,
+    /// 토큰 인증을 하지 않는 내부 요청에는 사용자가 없음
+    #[error("Unauthorized")]
+    Unauthorized,
+    /// 관리용 요청은 내부 요청에서만 사용할 수 있음
+    #[error("Forbidden")]
+    Forbidden,
 }
 
 /// Msg의 Payload는 같은 이름의 usecase의 Payload와는 관계가 없음
@@ -35,6 +48,18 @@ pub enum Msg {
     GetBooks(get_books::Payload),
     GetBook(get_book::Payload),
     GetBooksByIds(get_books_by_ids::Payload),
+    GetRandomBook(get_random_book::Payload),
+    GetBookFacets(get_book_facets::Payload),
+    GetBookTags(get_book_tags::Payload),
+    LookupBooks(lookup_books::Payload),
+    GetSavedSearches(get_saved_searches::Payload),
+    GetSavedSearch(get_saved_search::Payload),
+    CreateSavedSearch(create_saved_search::Payload),
+    UpdateSavedSearch(update_saved_search::Payload),
+    DeleteSavedSearch(delete_saved_search::Payload),
+    GetSavedSearchBooks(get_saved_search_books::Payload),
+    RecomputeTagStats(recompute_tag_stats::Payload),
+    GetBookCacheStats(get_book_cache_stats::Payload),
 }
 
 impl Msg {
@@ -55,14 +80,25 @@ impl Msg {
         }
 
         // 외부 사용자의 요청일 경우에는 토큰 인증을 함
-        if auth::check_internal(headers).is_err() {
+        let user_id = if auth::check_internal(headers).is_err() {
             let resp = RwLock::new(resp);
 
-            let _r = elapse!(
+            let user_info = elapse!(
                 "check_auth",
                 auth::check_and_refresh_token_pair(config.auth_url(), &resp, None).await?
             );
-        }
+
+            Some(user_info.user_id)
+        } else {
+            None
+        };
+
+        let me = || user_id.ok_or(Error::Unauthorized);
+
+        let internal = || match user_id {
+            Some(_) => Err(Error::Forbidden),
+            None => Ok(()),
+        };
 
         let method = request.method().clone();
         let path = request.uri().path();
@@ -79,10 +115,123 @@ impl Msg {
                 }
             }
 
+            (Method::GET, "/books/random") => Msg::GetRandomBook(request.try_into()?),
+
+            (Method::GET, "/books/facets") => Msg::GetBookFacets(request.try_into()?),
+
+            (Method::POST, "/books/lookup") => {
+                let lookup: lookup_books::Payload = payload::parse_json_body(request).await?;
+
+                Msg::LookupBooks(lookup.check(config.books_lookup_limit())?)
+            }
+
             (Method::GET, path) if matcher(path, "/books/:book_id") => {
                 Msg::GetBook(PathVariable::new(path, "/books/:book_id").try_into()?)
             }
 
+            (Method::GET, "/artists" | "/groups" | "/series" | "/characters") => {
+                // 위의 패턴에 해당하는 경로는 모두 디렉터리임
+                let kind = directory(path).unwrap();
+
+                Msg::GetBookTags((kind, request).try_into()?)
+            }
+
+            (Method::GET, path)
+                if matcher(path, "/:directory/:name/books") && directory(path).is_some() =>
+            {
+                let book_tag = book_tag_of(
+                    directory(path).unwrap(),
+                    PathVariable::new(path, "/:directory/:name/books"),
+                )?;
+
+                Msg::GetBooks((book_tag, request).try_into()?)
+            }
+
+            (Method::GET, "/me/saved-searches") => {
+                Msg::GetSavedSearches(get_saved_searches::Payload { user_id: me()? })
+            }
+
+            (Method::POST, "/me/saved-searches") => {
+                let create: create_saved_search::Payload =
+                    payload::parse_json_body(request).await?;
+
+                Msg::CreateSavedSearch(
+                    create_saved_search::Payload {
+                        user_id: me()?,
+                        ..create
+                    }
+                    .check()?,
+                )
+            }
+
+            (Method::GET, path) if matcher(path, "/me/saved-searches/:saved_search_id/books") => {
+                let get_saved_search::Payload {
+                    user_id,
+                    saved_search_id,
+                } = (
+                    me()?,
+                    PathVariable::new(path, "/me/saved-searches/:saved_search_id/books"),
+                )
+                    .try_into()?;
+
+                Msg::GetSavedSearchBooks((user_id, saved_search_id, request).try_into()?)
+            }
+
+            (Method::GET, path) if matcher(path, "/me/saved-searches/:saved_search_id") => {
+                Msg::GetSavedSearch(
+                    (
+                        me()?,
+                        PathVariable::new(path, "/me/saved-searches/:saved_search_id"),
+                    )
+                        .try_into()?,
+                )
+            }
+
+            (Method::PATCH, path) if matcher(path, "/me/saved-searches/:saved_search_id") => {
+                let get_saved_search::Payload {
+                    user_id,
+                    saved_search_id,
+                } = (
+                    me()?,
+                    PathVariable::new(path, "/me/saved-searches/:saved_search_id"),
+                )
+                    .try_into()?;
+
+                let update: update_saved_search::Payload =
+                    payload::parse_json_body(request).await?;
+
+                Msg::UpdateSavedSearch(
+                    update_saved_search::Payload {
+                        user_id,
+                        saved_search_id,
+                        ..update
+                    }
+                    .check()?,
+                )
+            }
+
+            (Method::DELETE, path) if matcher(path, "/me/saved-searches/:saved_search_id") => {
+                Msg::DeleteSavedSearch(
+                    (
+                        me()?,
+                        PathVariable::new(path, "/me/saved-searches/:saved_search_id"),
+                    )
+                        .try_into()?,
+                )
+            }
+
+            (Method::POST, "/tag-stats/recompute") => {
+                internal()?;
+
+                Msg::RecomputeTagStats(recompute_tag_stats::Payload {})
+            }
+
+            (Method::GET, "/book-cache/stats") => {
+                internal()?;
+
+                Msg::GetBookCacheStats(get_book_cache_stats::Payload {})
+            }
+
             _ => return Err(Error::NotFound.into()),
         };
 
@@ -92,6 +241,40 @@ impl Msg {
     }
 }
 
+/// 작품 목록에서 이름으로 찾아보는 태그 종류들
+///
+/// `/artists`, `/artists/:name/books` -> `artist`
+fn directory(req_path: &str) -> Option<BookTagKind> {
+    let directory = req_path.split('/').nth(1)?;
+
+    let kind = match directory {
+        "artists" => BookTagKind::Artist,
+        "groups" => BookTagKind::Group,
+        "series" => BookTagKind::Series,
+        "characters" => BookTagKind::Character,
+        _ => return None,
+    };
+
+    Some(kind)
+}
+
+/// `/:directory/:name/books`에서 태그를 읽음, 이름은 퍼센트 인코딩되어 있음
+fn book_tag_of(kind: BookTagKind, mut path_var: PathVariable) -> crate::Result<BookTag> {
+    let invalid_name = || payload::Error::InvalidPathVariable("name", "utf-8 string");
+
+    // :directory
+    path_var.next_variable::<String>();
+
+    let name = path_var
+        .next_variable::<String>()
+        .ok_or_else(invalid_name)?;
+    let name = percent_decode_str(&name)
+        .decode_utf8()
+        .map_err(|_| invalid_name())?;
+
+    Ok(kind.tag(name))
+}
+
 fn matcher(req_path: &str, pattern: &str) -> bool {
     let mut origin = req_path.split('/');
     let pats = pattern.split('/');
@@ -0,0 +1,52 @@
+use hyper::{body::HttpBody, header, Body, Request};
+use serde::de::DeserializeOwned;
+
+use super::Error;
+
+/// JSON 본문의 최대 크기, 바이트 단위
+pub const MAX_JSON_BODY_SIZE: usize = 1024 * 1024;
+
+/// `application/json`만 받음
+///
+/// 본문이 `MAX_JSON_BODY_SIZE`보다 크면 끝까지 읽지 않고 실패함
+pub async fn parse_json_body<T>(request: &mut Request<Body>) -> crate::Result<T>
+where
+    T: DeserializeOwned,
+{
+    let content_type = request
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|x| x.to_str().ok())
+        .unwrap_or_default();
+
+    if !content_type.starts_with("application/json") {
+        return Err(Error::NotSupportedContentType(content_type.to_owned()).into());
+    }
+
+    let content_length = request
+        .headers()
+        .get(header::CONTENT_LENGTH)
+        .and_then(|x| x.to_str().ok())
+        .and_then(|x| x.parse::<usize>().ok());
+
+    if matches!(content_length, Some(x) if x > MAX_JSON_BODY_SIZE) {
+        return Err(Error::TooLargeBody(MAX_JSON_BODY_SIZE).into());
+    }
+
+    // `Content-Length`가 없거나 실제 본문과 다를 수 있어서 읽으면서 다시 확인함
+    let mut body = Vec::with_capacity(content_length.unwrap_or_default());
+
+    while let Some(chunk) = request.body_mut().data().await {
+        let chunk = chunk?;
+
+        if body.len() + chunk.len() > MAX_JSON_BODY_SIZE {
+            return Err(Error::TooLargeBody(MAX_JSON_BODY_SIZE).into());
+        }
+
+        body.extend_from_slice(&chunk);
+    }
+
+    let payload = serde_json::from_slice(&body).map_err(Error::JsonDeserialize)?;
+
+    Ok(payload)
+}
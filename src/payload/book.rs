@@ -1,8 +1,16 @@
-use serde::Deserialize;
+use chrono::{DateTime, Utc};
+use itertools::Itertools;
+use serde::{Deserialize, Deserializer, Serialize};
+use util::validate::ValidatorNumberExt;
 
-use crate::entity::{self, Sort};
+use crate::{
+    entity::{self, Sort},
+    model,
+};
 
-#[derive(Debug, Deserialize)]
+use super::Error;
+
+#[derive(Debug, Clone, Copy, Deserialize)]
 #[serde(rename_all = "kebab-case")]
 pub enum BookSortBy {
     IdDesc,
@@ -10,19 +18,51 @@ pub enum BookSortBy {
     Random,
 }
 
-impl From<BookSortBy> for entity::BookSortBy {
-    fn from(sort_by: BookSortBy) -> Self {
+impl BookSortBy {
+    /// seed는 `Random`일 때만 사용됨
+    pub fn with_seed(self, seed: u32) -> entity::BookSortBy {
         use BookSortBy::*;
 
-        match sort_by {
-            IdDesc => Self::Id(Sort::Desc),
-            IdAsc => Self::Id(Sort::Asc),
-            Random => Self::Random,
+        match self {
+            IdDesc => entity::BookSortBy::Id(Sort::Desc),
+            IdAsc => entity::BookSortBy::Id(Sort::Asc),
+            Random => entity::BookSortBy::Random(seed),
         }
     }
 }
 
-#[derive(Debug, Deserialize)]
+/// `with-total=true`일 때 전체 갯수를 세는 방법
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum TotalMode {
+    Exact,
+    /// `pg_class.reltuples`로 추정함, 필터가 있으면 `Exact`와 같음
+    Estimated,
+}
+
+/// `fields=id,title,kind`와 `include-tags=false`를 합쳐서 응답에 포함할 필드를 정함
+pub fn book_fields(
+    fields: Option<&str>,
+    include_tags: Option<bool>,
+) -> Result<model::BookFields, Error> {
+    let mut book_fields = match fields {
+        Some(fields) => fields.parse().map_err(Error::InvalidField)?,
+        None => model::BookFields::default(),
+    };
+
+    if include_tags == Some(false) {
+        book_fields.tags = false;
+    }
+
+    Ok(book_fields)
+}
+
+/// seed를 받지 않은 요청에서 사용함
+pub fn random_seed() -> u32 {
+    uuid::Uuid::new_v4().as_u128() as u32
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, Serialize)]
 #[serde(rename_all = "kebab-case")]
 pub enum BookKind {
     Doujinshi,
@@ -43,3 +83,210 @@ impl From<BookKind> for entity::BookKind {
         }
     }
 }
+
+/// `get_books`처럼 작품 목록을 필터링하는 usecase들이 같이 사용함
+///
+/// `kind[]=manga&kind[]=doujinshi&language[]=korean&min-page=20&created-after=2022-01-01T00:00:00Z`
+///
+/// `tag[]=female:glasses&tag[]=artist:someone&exclude-tag[]=misc:full color`
+///
+/// 저장된 검색에는 이 형태 그대로 JSON으로 저장됨
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct BookFilter {
+    /// 목록 필터는 모두 예전 클라이언트가 보내는 `kind=manga`처럼 하나만 보낸 것도 받음
+    #[serde(
+        default,
+        deserialize_with = "one_or_many",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub kind: Vec<BookKind>,
+    #[serde(
+        default,
+        deserialize_with = "one_or_many",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub language: Vec<String>,
+    /// `kind:name`, 모든 태그가 붙은 작품만
+    #[serde(
+        default,
+        deserialize_with = "one_or_many",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub tag: Vec<String>,
+    /// `kind:name`, 하나라도 붙은 작품은 제외함
+    #[serde(
+        default,
+        deserialize_with = "one_or_many",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub exclude_tag: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_page: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_page: Option<usize>,
+    /// RFC 3339
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub created_after: Option<String>,
+    /// RFC 3339
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub created_before: Option<String>,
+}
+
+impl BookFilter {
+    pub fn check(self) -> Result<entity::BookFilter, Error> {
+        let kinds = self
+            .kind
+            .into_iter()
+            .map(entity::BookKind::from)
+            .unique()
+            .collect::<Vec<_>>();
+
+        if self.language.len() > 10 {
+            return Err(Error::TooManyLanguages(10));
+        }
+
+        let languages = self
+            .language
+            .into_iter()
+            .map(|language| {
+                let language = language.trim().to_lowercase();
+
+                if language.is_empty() || language.len() > 32 {
+                    Err(Error::InvalidLanguage(language))
+                } else {
+                    Ok(language)
+                }
+            })
+            .collect::<Result<Vec<_>, _>>()?
+            .into_iter()
+            .unique()
+            .collect::<Vec<_>>();
+
+        let tags = parse_tags("tag[]", self.tag)?;
+
+        let exclude_tags = parse_tags("exclude-tag[]", self.exclude_tag)?;
+
+        let min_page = self
+            .min_page
+            .map(|x| x.validate().min(1).take())
+            .transpose()
+            .map_err(Error::InvalidMinPage)?;
+
+        let max_page = self
+            .max_page
+            .map(|x| x.validate().min(1).take())
+            .transpose()
+            .map_err(Error::InvalidMaxPage)?;
+
+        if let (Some(min_page), Some(max_page)) = (min_page, max_page) {
+            if min_page > max_page {
+                return Err(Error::InvalidPageRange);
+            }
+        }
+
+        let created_after = self
+            .created_after
+            .as_deref()
+            .map(parse_timestamp)
+            .transpose()
+            .map_err(Error::InvalidCreatedAfter)?;
+
+        let created_before = self
+            .created_before
+            .as_deref()
+            .map(parse_timestamp)
+            .transpose()
+            .map_err(Error::InvalidCreatedBefore)?;
+
+        if let (Some(created_after), Some(created_before)) = (created_after, created_before) {
+            if created_after >= created_before {
+                return Err(Error::InvalidCreatedRange);
+            }
+        }
+
+        Ok(entity::BookFilter {
+            kinds,
+            languages,
+            min_page,
+            max_page,
+            created_after,
+            created_before,
+            after_id: None,
+            tags,
+            exclude_tags,
+        })
+    }
+}
+
+fn parse_tags(field: &'static str, xs: Vec<String>) -> Result<Vec<entity::BookTag>, Error> {
+    if xs.len() > 20 {
+        return Err(Error::TooManyTags(field, 20));
+    }
+
+    xs.into_iter()
+        .map(|x| {
+            let tag = x
+                .split_once(':')
+                .map(|(kind, name)| (kind.trim().to_lowercase(), name.trim()))
+                .filter(|(_, name)| !name.is_empty() && name.len() <= 100)
+                .and_then(|(kind, name)| entity::BookTag::new(&kind, name));
+
+            tag.ok_or(Error::InvalidTag(field, x))
+        })
+        .collect::<Result<Vec<_>, _>>()
+        .map(|tags| tags.into_iter().unique().collect())
+}
+
+/// `kind=manga`와 `kind[]=manga&kind[]=doujinshi`처럼 하나만 보낸 것과 여러개 보낸 것을 모두 받음
+fn one_or_many<'de, D, T>(deserializer: D) -> Result<Vec<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum OneOrMany<T> {
+        One(T),
+        Many(Vec<T>),
+    }
+
+    let xs = match OneOrMany::deserialize(deserializer)? {
+        OneOrMany::One(x) => vec![x],
+        OneOrMany::Many(xs) => xs,
+    };
+
+    Ok(xs)
+}
+
+fn parse_timestamp(x: &str) -> Result<DateTime<Utc>, chrono::ParseError> {
+    let x = x.trim();
+
+    // 쿼리스트링에서 인코딩되지 않은 `+`는 공백으로 디코딩되기 때문에 끝의 offset 앞에 있는 공백만 되돌림
+    // e.g. `2022-01-01T00:00:00+09:00` -> `2022-01-01T00:00:00 09:00`
+    //
+    // 날짜와 시각 사이의 공백은 그대로 둠, e.g. `2022-01-01 00:00:00Z`
+    let x = match x
+        .len()
+        .checked_sub(6)
+        .and_then(|i| Some((x.get(..i)?, x.get(i..)?)))
+    {
+        Some((datetime, offset)) if is_decoded_offset(offset) => {
+            format!("{datetime}+{}", &offset[1..])
+        }
+        _ => x.to_owned(),
+    };
+
+    DateTime::parse_from_rfc3339(&x).map(|x| x.with_timezone(&Utc))
+}
+
+/// ` HH:MM`
+fn is_decoded_offset(x: &str) -> bool {
+    let x = x.as_bytes();
+
+    x.len() == 6
+        && x[0] == b' '
+        && x[1..3].iter().all(u8::is_ascii_digit)
+        && x[3] == b':'
+        && x[4..].iter().all(u8::is_ascii_digit)
+}
@@ -0,0 +1,25 @@
+use serde::Deserialize;
+
+use crate::entity::{self, Sort};
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum BookTagSortBy {
+    BookCountDesc,
+    NameAsc,
+    NameDesc,
+    LatestDesc,
+}
+
+impl From<BookTagSortBy> for entity::BookTagSortBy {
+    fn from(sort_by: BookTagSortBy) -> Self {
+        use BookTagSortBy::*;
+
+        match sort_by {
+            BookCountDesc => Self::BookCount(Sort::Desc),
+            NameAsc => Self::Name(Sort::Asc),
+            NameDesc => Self::Name(Sort::Desc),
+            LatestDesc => Self::LatestCreatedAt(Sort::Desc),
+        }
+    }
+}
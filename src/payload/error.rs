@@ -6,6 +6,9 @@ pub enum Error {
     #[error("Not supported content-type: {0}")]
     NotSupportedContentType(String),
 
+    #[error("body must be less than or equal {0} bytes")]
+    TooLargeBody(usize),
+
     #[error("Json deserialize: {0}")]
     JsonDeserialize(serde_json::Error),
     #[error("Querystring deserialize: {0}")]
@@ -17,6 +20,34 @@ pub enum Error {
     InvalidPage(number::Error<usize>),
     #[error("sort-by: {0}")]
     InvalidSortBy(String),
+    #[error("language[]: invalid language `{0}`")]
+    InvalidLanguage(String),
+    #[error("language[]: length must be less than or equal {0}")]
+    TooManyLanguages(usize),
+    #[error("{0}: invalid tag `{1}`, must be `kind:name`")]
+    InvalidTag(&'static str, String),
+    #[error("{0}: length must be less than or equal {1}")]
+    TooManyTags(&'static str, usize),
+    #[error("min-page: {0}")]
+    InvalidMinPage(number::Error<usize>),
+    #[error("max-page: {0}")]
+    InvalidMaxPage(number::Error<usize>),
+    #[error("min-page must be less than or equal max-page")]
+    InvalidPageRange,
+    #[error("created-after: {0}")]
+    InvalidCreatedAfter(chrono::ParseError),
+    #[error("created-before: {0}")]
+    InvalidCreatedBefore(chrono::ParseError),
+    #[error("created-after must be earlier than created-before")]
+    InvalidCreatedRange,
+    #[error("length of ids must be less than or equal {0}")]
+    TooManyIds(usize),
+    #[error("fields: unknown field `{0}`")]
+    InvalidField(String),
+    #[error("name: length must be between 1 and 100")]
+    InvalidSavedSearchName,
+    #[error("tag-limit: {0}")]
+    InvalidTagLimit(number::Error<usize>),
     #[error("{0} must be {1}")]
     InvalidPathVariable(&'static str, &'static str),
 
@@ -1,5 +1,9 @@
+mod body;
 mod book;
+mod book_tag;
 mod error;
 
+pub use body::*;
 pub use book::*;
+pub use book_tag::*;
 pub use error::Error;
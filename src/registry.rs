@@ -8,7 +8,11 @@ mod root_registry {
         // command::CommandSet,
         config::Config,
         database::DatabaseSet,
-        repository::{PostgresqlBookRepository, RepositorySet},
+        repository::{
+            BookCacheListener, InMemoryBookRepository, InMemorySavedSearchRepository,
+            PostgresqlBookRepository, PostgresqlSavedSearchRepository, RepositorySet,
+            SqliteBookRepository, SqliteSavedSearchRepository,
+        },
     };
 
     combine_component_registry!(
@@ -28,7 +32,17 @@ mod root_registry {
 
     component_registry!(
         RepositoryRegistry,
-        [DatabaseSet, RepositorySet, PostgresqlBookRepository]
+        [
+            DatabaseSet,
+            RepositorySet,
+            BookCacheListener,
+            PostgresqlBookRepository,
+            PostgresqlSavedSearchRepository,
+            SqliteBookRepository,
+            SqliteSavedSearchRepository,
+            InMemoryBookRepository,
+            InMemorySavedSearchRepository
+        ]
     );
 
     // component_registry!(CommandRegistry, [CommandSet]);
@@ -0,0 +1,365 @@
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+
+use itertools::Itertools;
+use lru::LruCache;
+use parking_lot::Mutex;
+
+use crate::{
+    entity::{
+        Book, BookFacets, BookFilter, BookGroupByTag, BookSortBy, BookTag, BookTagKind,
+        BookTagSortBy, BookTagStatDrift, BookTagSummary,
+    },
+    repository::r#trait::BookRepository,
+};
+
+/// (book_id, include_tags)
+type Key = (u32, bool);
+
+struct Entry {
+    book: Book,
+    expires_at: Instant,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct CacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub len: usize,
+    pub capacity: usize,
+}
+
+/// 작품 하나씩 읽는 `get_one`과 `get_many_by_ids`만 캐시하고, 나머지는 그대로 넘김
+///
+/// 없는 작품은 캐시하지 않음, replica에 늦게 반영된 작품이 `ttl` 동안 없는 작품으로 남지 않게 하기 위함
+pub struct CachedBookRepository {
+    inner: Arc<dyn BookRepository>,
+    entries: Mutex<LruCache<Key, Entry>>,
+    ttl: Duration,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl CachedBookRepository {
+    pub fn new(inner: Arc<dyn BookRepository>, capacity: usize, ttl: Duration) -> Self {
+        Self {
+            inner,
+            entries: Mutex::new(LruCache::new(capacity)),
+            ttl,
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        let entries = self.entries.lock();
+
+        CacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            len: entries.len(),
+            capacity: entries.cap(),
+        }
+    }
+
+    /// 작품이 바뀌었을 때 호출함
+    pub fn invalidate(&self, book_id: u32) {
+        let mut entries = self.entries.lock();
+
+        entries.pop(&(book_id, true));
+        entries.pop(&(book_id, false));
+    }
+
+    pub fn clear(&self) {
+        self.entries.lock().clear();
+    }
+
+    /// 만료된 항목은 지우고 없는 것으로 취급함
+    fn get(&self, key: Key) -> Option<Book> {
+        let mut entries = self.entries.lock();
+
+        let book = entries
+            .get(&key)
+            .map(|entry| (entry.expires_at > Instant::now()).then(|| entry.book.clone()));
+
+        let book = match book {
+            Some(Some(book)) => Some(book),
+            Some(None) => {
+                entries.pop(&key);
+                None
+            }
+            None => None,
+        };
+
+        let counter = if book.is_some() {
+            &self.hits
+        } else {
+            &self.misses
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+
+        book
+    }
+
+    fn put(&self, include_tags: bool, book: &Book) {
+        let entry = Entry {
+            book: book.clone(),
+            expires_at: Instant::now() + self.ttl,
+        };
+
+        self.entries.lock().put((book.id, include_tags), entry);
+    }
+}
+
+#[async_trait::async_trait]
+impl BookRepository for CachedBookRepository {
+    async fn get_one(&self, book_id: u32) -> crate::Result<Option<Book>> {
+        if let Some(book) = self.get((book_id, true)) {
+            return Ok(Some(book));
+        }
+
+        let book = self.inner.get_one(book_id).await?;
+
+        if let Some(book) = &book {
+            self.put(true, book);
+        }
+
+        Ok(book)
+    }
+
+    async fn get_many(
+        &self,
+        filter: BookFilter,
+        per_page: usize,
+        page: usize,
+        sort_by: BookSortBy,
+        include_tags: bool,
+    ) -> crate::Result<Vec<Book>> {
+        self.inner
+            .get_many(filter, per_page, page, sort_by, include_tags)
+            .await
+    }
+
+    async fn count(&self, filter: BookFilter) -> crate::Result<usize> {
+        self.inner.count(filter).await
+    }
+
+    async fn estimate_count(&self) -> crate::Result<usize> {
+        self.inner.estimate_count().await
+    }
+
+    async fn get_facets(&self, filter: BookFilter, tag_limit: usize) -> crate::Result<BookFacets> {
+        self.inner.get_facets(filter, tag_limit).await
+    }
+
+    /// 캐시에 없는 작품들만 한번에 읽음
+    async fn get_many_by_ids(
+        &self,
+        book_ids: Vec<u32>,
+        include_tags: bool,
+    ) -> crate::Result<Vec<Book>> {
+        let book_ids = book_ids.into_iter().unique().collect::<Vec<_>>();
+
+        let cached = book_ids
+            .iter()
+            .map(|book_id| self.get((*book_id, include_tags)))
+            .collect::<Vec<_>>();
+
+        let missing_ids = book_ids
+            .iter()
+            .zip(&cached)
+            .filter(|(_, book)| book.is_none())
+            .map(|(book_id, _)| *book_id)
+            .collect::<Vec<_>>();
+
+        if missing_ids.is_empty() {
+            return Ok(cached.into_iter().flatten().collect());
+        }
+
+        let mut fetched = self
+            .inner
+            .get_many_by_ids_unordered(missing_ids, include_tags)
+            .await?
+            .into_iter()
+            .inspect(|book| self.put(include_tags, book))
+            .map(|book| (book.id, book))
+            .collect::<HashMap<_, _>>();
+
+        // 요청한 순서대로, 없는 작품은 제외함
+        let books = book_ids
+            .into_iter()
+            .zip(cached)
+            .filter_map(|(book_id, book)| book.or_else(|| fetched.remove(&book_id)))
+            .collect();
+
+        Ok(books)
+    }
+
+    /// 캐시에서 읽은 작품들과 합치면서 어차피 정렬하기 때문에 `get_many_by_ids`와 같음
+    async fn get_many_by_ids_unordered(
+        &self,
+        book_ids: Vec<u32>,
+        include_tags: bool,
+    ) -> crate::Result<Vec<Book>> {
+        self.get_many_by_ids(book_ids, include_tags).await
+    }
+
+    async fn get_tags(
+        &self,
+        kind: BookTagKind,
+        per_page: usize,
+        page: usize,
+        sort_by: BookTagSortBy,
+    ) -> crate::Result<Vec<BookTagSummary>> {
+        self.inner.get_tags(kind, per_page, page, sort_by).await
+    }
+
+    async fn get_many_by_tags(
+        &self,
+        book_tags: Vec<BookTag>,
+    ) -> crate::Result<Vec<BookGroupByTag>> {
+        self.inner.get_many_by_tags(book_tags).await
+    }
+
+    async fn get_many_by_tag(&self, book_tag: BookTag) -> crate::Result<Vec<Book>> {
+        self.inner.get_many_by_tag(book_tag).await
+    }
+
+    async fn add(&self, book: Book) -> crate::Result<bool> {
+        let book_id = book.id;
+
+        let added = self.inner.add(book).await?;
+
+        self.invalidate(book_id);
+
+        Ok(added)
+    }
+
+    async fn recompute_tag_stats(&self) -> crate::Result<Vec<BookTagStatDrift>> {
+        self.inner.recompute_tag_stats().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repository::{fixture::book_of, InMemoryBookRepository};
+
+    /// 1..=n 작품이 있는 `InMemoryBookRepository`를 캐시함
+    async fn setup(n: u32, capacity: usize, ttl: Duration) -> CachedBookRepository {
+        let inner = InMemoryBookRepository::default();
+
+        for id in 1..=n {
+            inner.add(book_of(id)).await.unwrap();
+        }
+
+        CachedBookRepository::new(Arc::new(inner), capacity, ttl)
+    }
+
+    fn hits_and_misses(repository: &CachedBookRepository) -> (u64, u64) {
+        let stats = repository.stats();
+
+        (stats.hits, stats.misses)
+    }
+
+    #[tokio::test]
+    async fn get_one_is_cached_until_ttl() {
+        let repository = setup(3, 10, Duration::from_secs(60)).await;
+
+        assert_eq!(repository.get_one(1).await.unwrap().unwrap().id, 1);
+        assert_eq!(repository.get_one(1).await.unwrap().unwrap().id, 1);
+        assert_eq!(hits_and_misses(&repository), (1, 1));
+
+        // 없는 작품은 캐시하지 않음
+        assert!(repository.get_one(4).await.unwrap().is_none());
+        assert!(repository.get_one(4).await.unwrap().is_none());
+        assert_eq!(hits_and_misses(&repository), (1, 3));
+        assert_eq!(repository.stats().len, 1);
+    }
+
+    #[tokio::test]
+    async fn expired_entries_are_fetched_again() {
+        let repository = setup(3, 10, Duration::ZERO).await;
+
+        assert_eq!(repository.get_one(1).await.unwrap().unwrap().id, 1);
+        assert_eq!(repository.get_one(1).await.unwrap().unwrap().id, 1);
+
+        assert_eq!(hits_and_misses(&repository), (0, 2));
+        assert_eq!(repository.stats().len, 1);
+    }
+
+    #[tokio::test]
+    async fn least_recently_used_entry_is_evicted() {
+        let repository = setup(3, 2, Duration::from_secs(60)).await;
+
+        repository.get_one(1).await.unwrap();
+        repository.get_one(2).await.unwrap();
+        // 2가 가장 오래 전에 사용됨
+        repository.get_one(1).await.unwrap();
+        repository.get_one(3).await.unwrap();
+        assert_eq!(hits_and_misses(&repository), (1, 3));
+
+        repository.get_one(1).await.unwrap();
+        repository.get_one(3).await.unwrap();
+        assert_eq!(hits_and_misses(&repository), (3, 3));
+
+        repository.get_one(2).await.unwrap();
+        assert_eq!(hits_and_misses(&repository), (3, 4));
+
+        let stats = repository.stats();
+        assert_eq!((stats.len, stats.capacity), (2, 2));
+    }
+
+    #[tokio::test]
+    async fn invalidate_removes_both_projections() {
+        let repository = setup(3, 10, Duration::from_secs(60)).await;
+
+        repository.get_one(1).await.unwrap();
+        repository.get_many_by_ids(vec![1], false).await.unwrap();
+        repository.get_one(2).await.unwrap();
+        assert_eq!(repository.stats().len, 3);
+
+        repository.invalidate(1);
+        assert_eq!(repository.stats().len, 1);
+
+        repository.get_one(1).await.unwrap();
+        assert_eq!(hits_and_misses(&repository), (0, 4));
+    }
+
+    #[tokio::test]
+    async fn get_many_by_ids_keeps_requested_order() {
+        let repository = setup(10, 20, Duration::from_secs(60)).await;
+
+        repository
+            .get_many_by_ids(vec![2, 4, 6], true)
+            .await
+            .unwrap();
+        assert_eq!(hits_and_misses(&repository), (0, 3));
+
+        let books = repository
+            .get_many_by_ids(vec![5, 4, 11, 1, 6, 4, 2], true)
+            .await
+            .unwrap();
+
+        // 중복과 없는 작품은 제외함
+        assert_eq!(
+            books.iter().map(|x| x.id).collect::<Vec<_>>(),
+            vec![5, 4, 1, 6, 2]
+        );
+        assert!(books.iter().all(|x| !x.tags.is_empty()));
+        assert_eq!(hits_and_misses(&repository), (3, 6));
+
+        // 태그 없이 읽은 작품은 따로 캐시함
+        let books = repository.get_many_by_ids(vec![4, 5], false).await.unwrap();
+
+        assert_eq!(books.iter().map(|x| x.id).collect::<Vec<_>>(), vec![4, 5]);
+        assert!(books.iter().all(|x| x.tags.is_empty()));
+        assert_eq!(hits_and_misses(&repository), (3, 8));
+    }
+}
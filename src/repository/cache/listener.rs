@@ -0,0 +1,136 @@
+use std::{sync::Arc, time::Duration};
+
+use sai::{Component, ComponentLifecycle, Injected};
+use sea_orm::sqlx::postgres::PgListener;
+use tokio::task::JoinHandle;
+
+use crate::{
+    config::{Config, LibraryStorage},
+    database::postgresql::{
+        notify::{parse_library_changes, LIBRARY_CHANGES},
+        pool::MAX_BACKOFF,
+    },
+    repository::RepositorySet,
+};
+
+use super::CachedBookRepository;
+
+/// 다른 인스턴스에서 바뀐 작품을 캐시에서 지움
+///
+/// postgresql을 사용하고 캐시가 있을 때만 동작함
+#[derive(Component)]
+#[lifecycle]
+pub struct BookCacheListener {
+    #[injected]
+    config: Injected<Config>,
+
+    #[injected]
+    repository: Injected<RepositorySet>,
+
+    listen: Option<JoinHandle<()>>,
+}
+
+#[async_trait::async_trait]
+impl ComponentLifecycle for BookCacheListener {
+    async fn start(&mut self) {
+        if self.config.library_storage() != LibraryStorage::Postgres {
+            return;
+        }
+
+        if let Some(book_cache) = self.repository.book_cache() {
+            self.listen.replace(tokio::spawn(Self::listen(
+                self.config.postgres_url().to_string(),
+                book_cache,
+            )));
+        }
+    }
+
+    async fn stop(&mut self) {
+        if let Some(listen) = self.listen.take() {
+            listen.abort();
+        }
+    }
+}
+
+impl BookCacheListener {
+    /// 연결이 끊겨 있던 동안 놓친 알림이 있을 수 있어서, 다시 연결하면 캐시를 모두 비움
+    async fn listen(url: String, book_cache: Arc<CachedBookRepository>) {
+        let mut backoff = Duration::from_secs(1);
+
+        loop {
+            let mut listener = match Self::connect(&url).await {
+                Ok(listener) => listener,
+                Err(err) => {
+                    log::warn!(
+                        "listen {}: {}; retry after {:?}",
+                        LIBRARY_CHANGES,
+                        err,
+                        backoff
+                    );
+
+                    tokio::time::sleep(backoff).await;
+
+                    backoff = (backoff * 2).min(MAX_BACKOFF);
+                    continue;
+                }
+            };
+
+            book_cache.clear();
+
+            log::info!("listen {}", LIBRARY_CHANGES);
+
+            loop {
+                match listener.try_recv().await {
+                    Ok(Some(notification)) => {
+                        // 연결하자마자 끊기는 동안에는 계속 늘어나도록 알림을 받았을 때만 되돌림
+                        backoff = Duration::from_secs(1);
+
+                        match parse_library_changes(notification.payload()) {
+                            Some(book_ids) => {
+                                for book_id in book_ids {
+                                    book_cache.invalidate(book_id);
+                                }
+                            }
+                            None => {
+                                log::warn!(
+                                    "invalid {} payload: {}",
+                                    LIBRARY_CHANGES,
+                                    notification.payload()
+                                );
+
+                                book_cache.clear();
+                            }
+                        }
+                    }
+                    // 다음 `try_recv`에서 다시 연결함
+                    Ok(None) => {
+                        log::warn!("lost {} connection; reconnecting", LIBRARY_CHANGES);
+
+                        book_cache.clear();
+                    }
+                    Err(err) => {
+                        log::warn!(
+                            "listen {}: {}; reconnect after {:?}",
+                            LIBRARY_CHANGES,
+                            err,
+                            backoff
+                        );
+
+                        tokio::time::sleep(backoff).await;
+
+                        backoff = (backoff * 2).min(MAX_BACKOFF);
+                        break;
+                    }
+                }
+            }
+        }
+    }
+
+    async fn connect(url: &str) -> Result<PgListener, sea_orm::sqlx::Error> {
+        let mut listener = PgListener::connect(url).await?;
+
+        listener.listen(LIBRARY_CHANGES).await?;
+
+        Ok(listener)
+    }
+}
@@ -0,0 +1,5 @@
+mod book;
+mod listener;
+
+pub use book::*;
+pub use listener::*;
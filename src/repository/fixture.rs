@@ -0,0 +1,49 @@
+//! 저장소 테스트에서 같이 사용하는 작품들
+
+use chrono::{TimeZone, Utc};
+use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
+
+use crate::entity::{Book, BookKind, BookTagKind};
+
+/// id가 클수록 태그가 많고, 같은 이름의 태그를 여러 작품이 공유함
+pub fn book_of(id: u32) -> Book {
+    let tags = (1..=id % 5)
+        .map(|i| BookTagKind::Misc.tag(format!("tag-{i}")))
+        .chain([BookTagKind::Artist.tag(format!("artist-{}", id % 3))])
+        .collect::<Vec<_>>();
+
+    Book {
+        id,
+        title: format!("book-{id}"),
+        page: id as usize,
+        language: "korean".to_string(),
+        kind: BookKind::Manga,
+        tags,
+        created_at: Utc.timestamp_opt(1_600_000_000 + id as i64, 0).unwrap(),
+    }
+}
+
+/// 1..=n 작품을 섞은 순서로, 작품마다 태그의 순서도 섞음
+pub fn shuffled_books(n: u32, rng: &mut StdRng) -> Vec<Book> {
+    let mut books = (1..=n).map(book_of).collect::<Vec<_>>();
+
+    books.shuffle(rng);
+
+    for book in &mut books {
+        book.tags.shuffle(rng);
+    }
+
+    books
+}
+
+/// 실패한 테스트를 같은 순서로 다시 실행할 수 있도록 seed를 같이 반환함, assert 메시지에 seed를 남겨야 함
+///
+/// `TEST_SEED`가 있으면 그 seed를 사용함
+pub fn seeded_rng() -> (u64, StdRng) {
+    let seed = match std::env::var("TEST_SEED") {
+        Ok(seed) => seed.parse().expect("TEST_SEED must be a number"),
+        Err(_) => rand::random(),
+    };
+
+    (seed, StdRng::seed_from_u64(seed))
+}
@@ -0,0 +1,486 @@
+use std::collections::{BTreeMap, HashMap};
+
+use chrono::{DateTime, Utc};
+use itertools::Itertools;
+use parking_lot::RwLock;
+use sai::Component;
+use uuid::Uuid;
+
+use crate::{
+    entity::{
+        Book, BookFacets, BookFilter, BookGroupByTag, BookSortBy, BookTag, BookTagKind,
+        BookTagSortBy, BookTagStatDrift, BookTagSummary, Sort,
+    },
+    repository::{
+        r#trait::{BookRepository, BOOKS_BY_TAG_LIMIT},
+        random_key_of,
+    },
+};
+
+/// 데이터베이스 없이 실행할 때 사용함
+///
+/// 정렬과 필터는 `PostgresqlBookRepository`의 쿼리와 같은 결과를 반환해야 함
+#[derive(Default, Component)]
+pub struct InMemoryBookRepository {
+    /// book_id -> (작품, random_key)
+    books: RwLock<BTreeMap<u32, (Book, f64)>>,
+}
+
+#[async_trait::async_trait]
+impl BookRepository for InMemoryBookRepository {
+    async fn get_one(&self, book_id: u32) -> crate::Result<Option<Book>> {
+        let books = self.books.read();
+
+        Ok(books.get(&book_id).map(|(book, _)| book.clone()))
+    }
+
+    async fn get_many(
+        &self,
+        filter: BookFilter,
+        per_page: usize,
+        page: usize,
+        sort_by: BookSortBy,
+        include_tags: bool,
+    ) -> crate::Result<Vec<Book>> {
+        let books = self.books.read();
+
+        let matched = books.values().filter(|(book, _)| matches(&filter, book));
+
+        let sorted = match sort_by {
+            BookSortBy::Id(Sort::Desc) => matched.rev().collect::<Vec<_>>(),
+            BookSortBy::Id(Sort::Asc) => matched.collect(),
+            BookSortBy::Random(seed) => {
+                let start = random_key_of(seed);
+
+                // 시작 지점 이후를 먼저 읽고, 끝에 도달하면 처음부터 다시 읽음
+                matched
+                    .sorted_by(|(a, a_key), (b, b_key)| {
+                        (*a_key < start)
+                            .cmp(&(*b_key < start))
+                            .then(a_key.total_cmp(b_key))
+                            .then(a.id.cmp(&b.id))
+                    })
+                    .collect()
+            }
+        };
+
+        let books = sorted
+            .into_iter()
+            .skip(per_page * (page - 1))
+            .take(per_page)
+            .map(|(book, _)| project(book, include_tags))
+            .collect();
+
+        Ok(books)
+    }
+
+    async fn count(&self, filter: BookFilter) -> crate::Result<usize> {
+        let books = self.books.read();
+
+        let count = books
+            .values()
+            .filter(|(book, _)| matches(&filter, book))
+            .count();
+
+        Ok(count)
+    }
+
+    async fn estimate_count(&self) -> crate::Result<usize> {
+        Ok(self.books.read().len())
+    }
+
+    async fn get_facets(&self, filter: BookFilter, tag_limit: usize) -> crate::Result<BookFacets> {
+        let books = self.books.read();
+
+        let matched = books
+            .values()
+            .map(|(book, _)| book)
+            .filter(|book| matches(&filter, book))
+            .collect::<Vec<_>>();
+
+        let kinds = matched
+            .iter()
+            .map(|book| book.kind)
+            .counts()
+            .into_iter()
+            .sorted_by(|(a, a_count), (b, b_count)| {
+                b_count.cmp(a_count).then(a.as_str().cmp(b.as_str()))
+            })
+            .collect();
+
+        let languages = matched
+            .iter()
+            .map(|book| book.language.clone())
+            .counts()
+            .into_iter()
+            .sorted_by(|(a, a_count), (b, b_count)| b_count.cmp(a_count).then(a.cmp(b)))
+            .collect();
+
+        // 태그 종류마다 작품 수가 많은 순서대로 `tag_limit`개씩
+        let tags = matched
+            .iter()
+            .flat_map(|book| book.tags.iter().cloned())
+            .counts()
+            .into_iter()
+            .sorted_by(|(a, a_count), (b, b_count)| {
+                a.kind()
+                    .cmp(b.kind())
+                    .then(b_count.cmp(a_count))
+                    .then(a.name().cmp(b.name()))
+            })
+            .group_by(|(tag, _)| tag.kind().to_owned())
+            .into_iter()
+            .flat_map(|(_, tags)| tags.take(tag_limit).collect::<Vec<_>>())
+            .collect();
+
+        Ok(BookFacets {
+            kinds,
+            languages,
+            tags,
+        })
+    }
+
+    async fn get_many_by_ids(
+        &self,
+        book_ids: Vec<u32>,
+        include_tags: bool,
+    ) -> crate::Result<Vec<Book>> {
+        let books = self.books.read();
+
+        // 요청한 순서대로, 없는 작품은 제외함
+        let books = book_ids
+            .into_iter()
+            .unique()
+            .filter_map(|book_id| books.get(&book_id))
+            .map(|(book, _)| project(book, include_tags))
+            .collect();
+
+        Ok(books)
+    }
+
+    /// 정렬하는 비용이 없어서 `get_many_by_ids`와 같음
+    async fn get_many_by_ids_unordered(
+        &self,
+        book_ids: Vec<u32>,
+        include_tags: bool,
+    ) -> crate::Result<Vec<Book>> {
+        self.get_many_by_ids(book_ids, include_tags).await
+    }
+
+    async fn get_tags(
+        &self,
+        kind: BookTagKind,
+        per_page: usize,
+        page: usize,
+        sort_by: BookTagSortBy,
+    ) -> crate::Result<Vec<BookTagSummary>> {
+        let books = self.books.read();
+
+        // name -> (book_count, latest_created_at)
+        let mut tags = HashMap::<&str, (usize, DateTime<Utc>)>::new();
+
+        for (book, _) in books.values() {
+            for tag in book.tags.iter().filter(|tag| tag.kind() == kind.as_str()) {
+                let (book_count, latest_created_at) =
+                    tags.entry(tag.name()).or_insert((0, book.created_at));
+
+                *book_count += 1;
+                *latest_created_at = (*latest_created_at).max(book.created_at);
+            }
+        }
+
+        let sort = |sort: Sort, ordering: std::cmp::Ordering| match sort {
+            Sort::Desc => ordering.reverse(),
+            Sort::Asc => ordering,
+        };
+
+        // 같은 값끼리는 이름순
+        let tags = tags
+            .into_iter()
+            .sorted_by(
+                |(a, (a_count, a_latest)), (b, (b_count, b_latest))| match sort_by {
+                    BookTagSortBy::BookCount(x) => sort(x, a_count.cmp(b_count)).then(a.cmp(b)),
+                    BookTagSortBy::Name(x) => sort(x, a.cmp(b)),
+                    BookTagSortBy::LatestCreatedAt(x) => {
+                        sort(x, a_latest.cmp(b_latest)).then(a.cmp(b))
+                    }
+                },
+            )
+            .skip(per_page * (page - 1))
+            .take(per_page)
+            .map(|(name, (book_count, latest_created_at))| BookTagSummary {
+                tag: kind.tag(name),
+                book_count,
+                latest_created_at,
+            })
+            .collect();
+
+        Ok(tags)
+    }
+
+    async fn get_many_by_tags(
+        &self,
+        book_tags: Vec<BookTag>,
+    ) -> crate::Result<Vec<BookGroupByTag>> {
+        let mut groups = Vec::new();
+
+        for tag in book_tags {
+            let books = self.get_many_by_tag(tag.clone()).await?;
+
+            groups.push(BookGroupByTag { tag, books });
+        }
+
+        Ok(groups)
+    }
+
+    async fn get_many_by_tag(&self, book_tag: BookTag) -> crate::Result<Vec<Book>> {
+        let books = self.books.read();
+
+        let books = books
+            .values()
+            .rev()
+            .filter(|(book, _)| book.tags.contains(&book_tag))
+            .take(BOOKS_BY_TAG_LIMIT)
+            .map(|(book, _)| book.clone())
+            .collect();
+
+        Ok(books)
+    }
+
+    async fn add(&self, book: Book) -> crate::Result<bool> {
+        let mut books = self.books.write();
+
+        if books.contains_key(&book.id) {
+            return Ok(false);
+        }
+
+        // 데이터베이스의 `DEFAULT random()`처럼 작품마다 한번만 정함
+        let random_key = random_key_of(Uuid::new_v4().as_u128() as u32);
+
+        // 같은 태그가 여러번 붙어있어도 하나만 저장됨
+        let tags = book.tags.iter().cloned().unique().collect();
+
+        books.insert(book.id, (Book { tags, ..book }, random_key));
+
+        Ok(true)
+    }
+
+    /// 태그 통계를 따로 저장하지 않고 매번 작품들에서 세기 때문에 달라질 수 없음
+    async fn recompute_tag_stats(&self) -> crate::Result<Vec<BookTagStatDrift>> {
+        Ok(Vec::new())
+    }
+}
+
+/// `include_tags`가 false면 태그를 비워서 반환함
+fn project(book: &Book, include_tags: bool) -> Book {
+    let mut book = book.clone();
+
+    if !include_tags {
+        book.tags.clear();
+    }
+
+    book
+}
+
+/// `PostgresqlBookRepository`의 `filter_sql`과 같은 조건
+fn matches(filter: &BookFilter, book: &Book) -> bool {
+    let BookFilter {
+        kinds,
+        languages,
+        min_page,
+        max_page,
+        created_after,
+        created_before,
+        after_id,
+        tags,
+        exclude_tags,
+    } = filter;
+
+    (kinds.is_empty() || kinds.contains(&book.kind))
+        && (languages.is_empty() || languages.contains(&book.language))
+        && min_page.map_or(true, |x| book.page >= x)
+        && max_page.map_or(true, |x| book.page <= x)
+        && created_after.map_or(true, |x| book.created_at > x)
+        && created_before.map_or(true, |x| book.created_at < x)
+        && after_id.map_or(true, |x| book.id > x)
+        && tags.iter().all(|tag| book.tags.contains(tag))
+        && !exclude_tags.iter().any(|tag| book.tags.contains(tag))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
+
+    use super::*;
+    use crate::repository::fixture::{book_of, seeded_rng, shuffled_books};
+
+    /// 1..=n 작품을 섞은 순서대로 추가함, 섞을 때 사용한 seed를 같이 반환함
+    async fn setup(n: u32) -> (InMemoryBookRepository, u64) {
+        let repository = InMemoryBookRepository::default();
+
+        let (seed, mut rng) = seeded_rng();
+
+        for book in shuffled_books(n, &mut rng) {
+            assert!(repository.add(book).await.unwrap(), "seed {seed}");
+        }
+
+        (repository, seed)
+    }
+
+    async fn pages(
+        repository: &InMemoryBookRepository,
+        per_page: usize,
+        sort_by: BookSortBy,
+    ) -> Vec<Book> {
+        let mut books = Vec::new();
+
+        for page in 1.. {
+            let xs = repository
+                .get_many(BookFilter::default(), per_page, page, sort_by, true)
+                .await
+                .unwrap();
+
+            if xs.is_empty() {
+                break;
+            }
+
+            books.extend(xs);
+        }
+
+        books
+    }
+
+    fn ids(books: &[Book]) -> Vec<u32> {
+        books.iter().map(|x| x.id).collect()
+    }
+
+    #[tokio::test]
+    async fn get_many_sorts_shuffled_books() {
+        let (repository, seed) = setup(30).await;
+
+        let desc = pages(&repository, 7, BookSortBy::Id(Sort::Desc)).await;
+        let asc = pages(&repository, 7, BookSortBy::Id(Sort::Asc)).await;
+
+        assert_eq!(
+            ids(&desc),
+            (1..=30).rev().collect::<Vec<_>>(),
+            "seed {seed}"
+        );
+        assert_eq!(ids(&asc), (1..=30).collect::<Vec<_>>(), "seed {seed}");
+    }
+
+    #[tokio::test]
+    async fn get_many_random_pages_follow_one_order() {
+        let (repository, seed) = setup(30).await;
+        let sort_by = BookSortBy::Random(42);
+
+        let paged = pages(&repository, 7, sort_by).await;
+        let whole = repository
+            .get_many(BookFilter::default(), 30, 1, sort_by, true)
+            .await
+            .unwrap();
+
+        assert_eq!(ids(&paged), ids(&whole), "seed {seed}");
+        assert_eq!(
+            whole.iter().map(|x| x.id).collect::<HashSet<_>>(),
+            (1..=30).collect::<HashSet<_>>(),
+            "seed {seed}"
+        );
+    }
+
+    #[tokio::test]
+    async fn get_many_filters_and_counts() {
+        let (repository, seed) = setup(30).await;
+
+        let filter = BookFilter {
+            tags: vec![BookTagKind::Misc.tag("tag-2")],
+            exclude_tags: vec![BookTagKind::Artist.tag("artist-0")],
+            min_page: Some(10),
+            ..Default::default()
+        };
+        let expected = (10..=30)
+            .filter(|id| id % 5 >= 2 && id % 3 != 0)
+            .collect::<Vec<_>>();
+
+        let books = repository
+            .get_many(filter.clone(), 30, 1, BookSortBy::Id(Sort::Asc), false)
+            .await
+            .unwrap();
+
+        assert_eq!(ids(&books), expected, "seed {seed}");
+        assert!(books.iter().all(|x| x.tags.is_empty()), "seed {seed}");
+        assert_eq!(
+            repository.count(filter).await.unwrap(),
+            expected.len(),
+            "seed {seed}"
+        );
+    }
+
+    #[tokio::test]
+    async fn get_many_by_ids_keeps_requested_order() {
+        let (repository, seed) = setup(30).await;
+
+        let mut book_ids = (1..=30).collect::<Vec<_>>();
+        book_ids.shuffle(&mut StdRng::seed_from_u64(seed));
+
+        let books = repository
+            .get_many_by_ids(book_ids.iter().copied().chain([31, 32]).collect(), true)
+            .await
+            .unwrap();
+
+        assert_eq!(ids(&books), book_ids, "seed {seed}");
+    }
+
+    #[tokio::test]
+    async fn get_many_by_tag_returns_latest_first() {
+        let (repository, seed) = setup(30).await;
+
+        let books = repository
+            .get_many_by_tag(BookTagKind::Misc.tag("tag-4"))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            ids(&books),
+            (1..=30).rev().filter(|id| id % 5 == 4).collect::<Vec<_>>(),
+            "seed {seed}"
+        );
+    }
+
+    #[tokio::test]
+    async fn get_many_by_tag_is_limited() {
+        let n = BOOKS_BY_TAG_LIMIT as u32 * 3 + 3;
+        let (repository, seed) = setup(n).await;
+
+        let books = repository
+            .get_many_by_tag(BookTagKind::Artist.tag("artist-0"))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            ids(&books),
+            (1..=n)
+                .rev()
+                .filter(|id| id % 3 == 0)
+                .take(BOOKS_BY_TAG_LIMIT)
+                .collect::<Vec<_>>(),
+            "seed {seed}"
+        );
+    }
+
+    #[tokio::test]
+    async fn add_rejects_duplicates_and_dedups_tags() {
+        let repository = InMemoryBookRepository::default();
+
+        let mut book = book_of(4);
+        book.tags.push(book.tags[0].clone());
+
+        assert!(repository.add(book.clone()).await.unwrap());
+        assert!(!repository.add(book).await.unwrap());
+
+        let book = repository.get_one(4).await.unwrap().unwrap();
+        assert_eq!(book.tags.len(), book_of(4).tags.len());
+    }
+}
@@ -1 +1,5 @@
+mod book;
+mod saved_search;
 
+pub use book::*;
+pub use saved_search::*;
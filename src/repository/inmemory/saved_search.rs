@@ -0,0 +1,127 @@
+use std::collections::BTreeMap;
+
+use parking_lot::RwLock;
+use sai::Component;
+use uuid::Uuid;
+
+use crate::{entity::SavedSearch, repository::r#trait::SavedSearchRepository};
+
+#[derive(Component)]
+pub struct InMemorySavedSearchRepository {
+    /// (마지막으로 사용한 id, saved_search_id -> 저장된 검색)
+    saved_searches: RwLock<(u64, BTreeMap<u64, SavedSearch>)>,
+}
+
+#[async_trait::async_trait]
+impl SavedSearchRepository for InMemorySavedSearchRepository {
+    async fn get_one(
+        &self,
+        user_id: Uuid,
+        saved_search_id: u64,
+    ) -> crate::Result<Option<SavedSearch>> {
+        let (_, saved_searches) = &*self.saved_searches.read();
+
+        let saved_search = saved_searches
+            .get(&saved_search_id)
+            .filter(|x| x.user_id == user_id)
+            .cloned();
+
+        Ok(saved_search)
+    }
+
+    async fn get_many(&self, user_id: Uuid) -> crate::Result<Vec<SavedSearch>> {
+        let (_, saved_searches) = &*self.saved_searches.read();
+
+        let saved_searches = saved_searches
+            .values()
+            .filter(|x| x.user_id == user_id)
+            .cloned()
+            .collect();
+
+        Ok(saved_searches)
+    }
+
+    async fn add(
+        &self,
+        saved_search: SavedSearch,
+        max: usize,
+    ) -> crate::Result<Option<SavedSearch>> {
+        let (last_id, saved_searches) = &mut *self.saved_searches.write();
+
+        let count = saved_searches
+            .values()
+            .filter(|x| x.user_id == saved_search.user_id)
+            .count();
+
+        if count >= max {
+            return Ok(None);
+        }
+
+        // bigserial처럼 1부터 시작함
+        *last_id += 1;
+
+        let saved_search = SavedSearch {
+            id: *last_id,
+            ..saved_search
+        };
+
+        saved_searches.insert(saved_search.id, saved_search.clone());
+
+        Ok(Some(saved_search))
+    }
+
+    async fn update(&self, saved_search: SavedSearch) -> crate::Result<bool> {
+        let (_, saved_searches) = &mut *self.saved_searches.write();
+
+        match saved_searches
+            .get_mut(&saved_search.id)
+            .filter(|x| x.user_id == saved_search.user_id)
+        {
+            Some(x) => {
+                x.name = saved_search.name;
+                x.filter = saved_search.filter;
+                x.last_seen_max_id = saved_search.last_seen_max_id;
+                x.updated_at = saved_search.updated_at;
+
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    async fn mark_seen(
+        &self,
+        user_id: Uuid,
+        saved_search_id: u64,
+        max_id: u32,
+    ) -> crate::Result<bool> {
+        let (_, saved_searches) = &mut *self.saved_searches.write();
+
+        match saved_searches
+            .get_mut(&saved_search_id)
+            .filter(|x| x.user_id == user_id)
+        {
+            Some(x) => {
+                x.last_seen_max_id = x.last_seen_max_id.max(max_id);
+
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    async fn remove(&self, user_id: Uuid, saved_search_id: u64) -> crate::Result<bool> {
+        let (_, saved_searches) = &mut *self.saved_searches.write();
+
+        let is_owner = saved_searches
+            .get(&saved_search_id)
+            .map(|x| x.user_id == user_id)
+            .unwrap_or(false);
+
+        if is_owner {
+            saved_searches.remove(&saved_search_id);
+        }
+
+        Ok(is_owner)
+    }
+}
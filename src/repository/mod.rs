@@ -1,22 +1,144 @@
+mod cache;
+#[cfg(test)]
+mod fixture;
 mod inmemory;
 mod postgresql;
+mod single_flight;
+mod sql;
+mod sqlite;
 pub mod r#trait;
 
+pub use cache::*;
 pub use inmemory::*;
 pub use postgresql::*;
+pub use single_flight::*;
+pub use sqlite::*;
 
 use std::sync::Arc;
 
-use sai::{Component, Injected};
+use sai::{Component, ComponentLifecycle, Injected};
 
+use crate::config::{Config, LibraryStorage};
+
+use self::r#trait::{BookRepository, SavedSearchRepository};
+
+/// `LIBRARY_STORAGE`에 따라 사용할 저장소를 정함
+///
+/// 모든 저장소가 생성되지만, 사용하지 않는 저장소는 아무것도 하지 않음
 #[derive(Component)]
+#[lifecycle]
 pub struct RepositorySet {
     #[injected]
-    book_repository: Injected<PostgresqlBookRepository>,
+    config: Injected<Config>,
+
+    #[injected]
+    postgresql_book_repository: Injected<PostgresqlBookRepository>,
+
+    #[injected]
+    postgresql_saved_search_repository: Injected<PostgresqlSavedSearchRepository>,
+
+    #[injected]
+    sqlite_book_repository: Injected<SqliteBookRepository>,
+
+    #[injected]
+    sqlite_saved_search_repository: Injected<SqliteSavedSearchRepository>,
+
+    #[injected]
+    inmemory_book_repository: Injected<InMemoryBookRepository>,
+
+    #[injected]
+    inmemory_saved_search_repository: Injected<InMemorySavedSearchRepository>,
+
+    book_repository: Option<Arc<dyn BookRepository>>,
+
+    /// `book_repository`를 감싸고 있는 캐시, 메모리 저장소이거나 `BOOK_CACHE_SIZE=0`이면 없음
+    book_cache: Option<Arc<CachedBookRepository>>,
+
+    saved_search_repository: Option<Arc<dyn SavedSearchRepository>>,
+}
+
+#[async_trait::async_trait]
+impl ComponentLifecycle for RepositorySet {
+    async fn start(&mut self) {
+        let library_storage = self.config.library_storage();
+
+        let (book_repository, saved_search_repository): (
+            Arc<dyn BookRepository>,
+            Arc<dyn SavedSearchRepository>,
+        ) = match library_storage {
+            LibraryStorage::Postgres => (
+                Arc::<PostgresqlBookRepository>::clone(&self.postgresql_book_repository),
+                Arc::<PostgresqlSavedSearchRepository>::clone(
+                    &self.postgresql_saved_search_repository,
+                ),
+            ),
+            LibraryStorage::Sqlite => (
+                Arc::<SqliteBookRepository>::clone(&self.sqlite_book_repository),
+                Arc::<SqliteSavedSearchRepository>::clone(&self.sqlite_saved_search_repository),
+            ),
+            LibraryStorage::Memory => (
+                Arc::<InMemoryBookRepository>::clone(&self.inmemory_book_repository),
+                Arc::<InMemorySavedSearchRepository>::clone(&self.inmemory_saved_search_repository),
+            ),
+        };
+
+        // 캐시에 없는 작품을 읽는 요청들만 합쳐짐
+        let book_repository: Arc<dyn BookRepository> =
+            if library_storage != LibraryStorage::Memory && self.config.single_flight() {
+                Arc::new(SingleFlightBookRepository::new(book_repository))
+            } else {
+                book_repository
+            };
+
+        let cache_size = self.config.book_cache_size();
+
+        let book_repository: Arc<dyn BookRepository> =
+            if library_storage != LibraryStorage::Memory && cache_size > 0 {
+                let book_cache = Arc::new(CachedBookRepository::new(
+                    book_repository,
+                    cache_size,
+                    self.config.book_cache_ttl(),
+                ));
+
+                self.book_cache.replace(Arc::clone(&book_cache));
+
+                book_cache
+            } else {
+                book_repository
+            };
+
+        log::info!("library storage = {library_storage:?}");
+
+        self.book_repository.replace(book_repository);
+        self.saved_search_repository
+            .replace(saved_search_repository);
+    }
 }
 
 impl RepositorySet {
-    pub fn book(&self) -> Arc<impl r#trait::BookRepository> {
-        Arc::clone(&self.book_repository)
+    pub fn book(&self) -> Arc<dyn BookRepository> {
+        Arc::clone(self.book_repository.as_ref().unwrap())
+    }
+
+    pub fn book_cache(&self) -> Option<Arc<CachedBookRepository>> {
+        self.book_cache.clone()
+    }
+
+    pub fn saved_search(&self) -> Arc<dyn SavedSearchRepository> {
+        Arc::clone(self.saved_search_repository.as_ref().unwrap())
     }
 }
+
+/// seed를 `[0, 1)` 범위의 시작 지점으로 바꿈
+///
+/// 가까운 seed끼리도 시작 지점이 멀리 떨어지도록 섞어줌
+///
+/// 작품마다 정해둔 `random_key`가 이 지점 이상인 작품부터 순서대로 읽고, 끝에 도달하면 처음부터 다시 읽음
+///
+/// seed마다 따로 섞는 것이 아님, 모든 seed가 `random_key` 순서 하나를 공유하고 읽기 시작하는 지점만 다름
+/// 그래서 seed가 달라도 작품들의 앞뒤 관계는 같고, 순서를 돌려놓은 것처럼 보임
+pub(crate) fn random_key_of(seed: u32) -> f64 {
+    let mixed = (seed as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15) >> 11;
+
+    mixed as f64 / (1_u64 << 53) as f64
+}
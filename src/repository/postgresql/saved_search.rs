@@ -0,0 +1,78 @@
+use sai::{Component, Injected};
+use sea_orm::{ConnectionTrait, DbErr, Statement, TransactionTrait};
+use uuid::Uuid;
+
+use crate::{
+    database::DatabaseSet,
+    entity::SavedSearch,
+    repository::{r#trait::SavedSearchRepository, sql},
+};
+
+#[derive(Component)]
+pub struct PostgresqlSavedSearchRepository {
+    #[injected]
+    database: Injected<DatabaseSet>,
+}
+
+/// 쿼리는 sqlite와 같이 사용함, `repository::sql::saved_search` 참조
+///
+/// 사용자가 방금 저장하거나 수정한 검색을 바로 읽기 때문에 replica를 사용하지 않음
+#[async_trait::async_trait]
+impl SavedSearchRepository for PostgresqlSavedSearchRepository {
+    async fn get_one(
+        &self,
+        user_id: Uuid,
+        saved_search_id: u64,
+    ) -> crate::Result<Option<SavedSearch>> {
+        sql::saved_search::get_one(self.database.postgresql(), user_id, saved_search_id).await
+    }
+
+    async fn get_many(&self, user_id: Uuid) -> crate::Result<Vec<SavedSearch>> {
+        sql::saved_search::get_many(self.database.postgresql(), user_id).await
+    }
+
+    /// READ COMMITTED에서는 동시에 추가하는 요청들이 서로의 행을 세지 못해서, 사용자마다 advisory lock을 잡고 추가함
+    async fn add(
+        &self,
+        saved_search: SavedSearch,
+        max: usize,
+    ) -> crate::Result<Option<SavedSearch>> {
+        let db = self.database.postgresql();
+
+        let saved_search = db
+            .transaction::<_, _, DbErr>(|txn| {
+                Box::pin(async move {
+                    let stmt = Statement::from_sql_and_values(
+                        txn.get_database_backend(),
+                        "SELECT pg_advisory_xact_lock(hashtext($1))",
+                        [saved_search.user_id.to_string().into()],
+                    );
+
+                    txn.execute(stmt).await?;
+
+                    sql::saved_search::add(txn, saved_search, max).await
+                })
+            })
+            .await?;
+
+        Ok(saved_search)
+    }
+
+    async fn update(&self, saved_search: SavedSearch) -> crate::Result<bool> {
+        sql::saved_search::update(self.database.postgresql(), saved_search).await
+    }
+
+    async fn mark_seen(
+        &self,
+        user_id: Uuid,
+        saved_search_id: u64,
+        max_id: u32,
+    ) -> crate::Result<bool> {
+        sql::saved_search::mark_seen(self.database.postgresql(), user_id, saved_search_id, max_id)
+            .await
+    }
+
+    async fn remove(&self, user_id: Uuid, saved_search_id: u64) -> crate::Result<bool> {
+        sql::saved_search::remove(self.database.postgresql(), user_id, saved_search_id).await
+    }
+}
@@ -0,0 +1,129 @@
+use std::sync::Arc;
+
+use crate::{
+    entity::{
+        Book, BookFacets, BookFilter, BookGroupByTag, BookSortBy, BookTag, BookTagKind,
+        BookTagSortBy, BookTagStatDrift, BookTagSummary,
+    },
+    repository::r#trait::BookRepository,
+};
+
+use super::SingleFlight;
+
+/// (filter, per_page, page, sort_by, include_tags)
+type GetManyKey = (BookFilter, usize, usize, BookSortBy, bool);
+
+/// 같은 인자로 동시에 들어온 `get_one`, `get_many`, `count`, `get_many_by_ids`는 쿼리 하나의 결과를 나눠 받음
+///
+/// 나머지는 그대로 넘김
+pub struct SingleFlightBookRepository {
+    inner: Arc<dyn BookRepository>,
+    get_one: SingleFlight<u32, Option<Book>>,
+    get_many: SingleFlight<GetManyKey, Vec<Book>>,
+    count: SingleFlight<BookFilter, usize>,
+    get_many_by_ids: SingleFlight<(Vec<u32>, bool), Vec<Book>>,
+}
+
+impl SingleFlightBookRepository {
+    pub fn new(inner: Arc<dyn BookRepository>) -> Self {
+        Self {
+            inner,
+            get_one: SingleFlight::default(),
+            get_many: SingleFlight::default(),
+            count: SingleFlight::default(),
+            get_many_by_ids: SingleFlight::default(),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl BookRepository for SingleFlightBookRepository {
+    async fn get_one(&self, book_id: u32) -> crate::Result<Option<Book>> {
+        self.get_one
+            .run(book_id, || self.inner.get_one(book_id))
+            .await
+    }
+
+    async fn get_many(
+        &self,
+        filter: BookFilter,
+        per_page: usize,
+        page: usize,
+        sort_by: BookSortBy,
+        include_tags: bool,
+    ) -> crate::Result<Vec<Book>> {
+        let key = (filter.clone(), per_page, page, sort_by, include_tags);
+
+        self.get_many
+            .run(key, || {
+                self.inner
+                    .get_many(filter, per_page, page, sort_by, include_tags)
+            })
+            .await
+    }
+
+    async fn count(&self, filter: BookFilter) -> crate::Result<usize> {
+        self.count
+            .run(filter.clone(), || self.inner.count(filter))
+            .await
+    }
+
+    async fn estimate_count(&self) -> crate::Result<usize> {
+        self.inner.estimate_count().await
+    }
+
+    async fn get_facets(&self, filter: BookFilter, tag_limit: usize) -> crate::Result<BookFacets> {
+        self.inner.get_facets(filter, tag_limit).await
+    }
+
+    async fn get_many_by_ids(
+        &self,
+        book_ids: Vec<u32>,
+        include_tags: bool,
+    ) -> crate::Result<Vec<Book>> {
+        self.get_many_by_ids
+            .run((book_ids.clone(), include_tags), || {
+                self.inner.get_many_by_ids(book_ids, include_tags)
+            })
+            .await
+    }
+
+    async fn get_many_by_ids_unordered(
+        &self,
+        book_ids: Vec<u32>,
+        include_tags: bool,
+    ) -> crate::Result<Vec<Book>> {
+        self.inner
+            .get_many_by_ids_unordered(book_ids, include_tags)
+            .await
+    }
+
+    async fn get_tags(
+        &self,
+        kind: BookTagKind,
+        per_page: usize,
+        page: usize,
+        sort_by: BookTagSortBy,
+    ) -> crate::Result<Vec<BookTagSummary>> {
+        self.inner.get_tags(kind, per_page, page, sort_by).await
+    }
+
+    async fn get_many_by_tags(
+        &self,
+        book_tags: Vec<BookTag>,
+    ) -> crate::Result<Vec<BookGroupByTag>> {
+        self.inner.get_many_by_tags(book_tags).await
+    }
+
+    async fn get_many_by_tag(&self, book_tag: BookTag) -> crate::Result<Vec<Book>> {
+        self.inner.get_many_by_tag(book_tag).await
+    }
+
+    async fn add(&self, book: Book) -> crate::Result<bool> {
+        self.inner.add(book).await
+    }
+
+    async fn recompute_tag_stats(&self) -> crate::Result<Vec<BookTagStatDrift>> {
+        self.inner.recompute_tag_stats().await
+    }
+}
@@ -0,0 +1,220 @@
+use std::{collections::HashMap, future::Future, hash::Hash, sync::Arc};
+
+use parking_lot::Mutex;
+use tokio::sync::oneshot;
+
+use crate::error::RepositoryError;
+
+type Shared<V> = Result<V, Arc<crate::Error>>;
+
+/// 같은 키로 동시에 들어온 요청은 먼저 시작한 요청의 결과를 같이 받음
+///
+/// 먼저 시작한 요청이 실패하면 에러도 같이 받고, 취소되면 기다리던 요청 중 하나가 이어서 실행함
+pub struct SingleFlight<K, V> {
+    in_flight: Mutex<HashMap<K, Vec<oneshot::Sender<Shared<V>>>>>,
+}
+
+impl<K, V> Default for SingleFlight<K, V> {
+    fn default() -> Self {
+        Self {
+            in_flight: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+impl<K, V> SingleFlight<K, V>
+where
+    K: Hash + Eq + Clone,
+    V: Clone,
+{
+    pub async fn run<F, Fut>(&self, key: K, f: F) -> crate::Result<V>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = crate::Result<V>>,
+    {
+        loop {
+            let waiting = {
+                let mut in_flight = self.in_flight.lock();
+
+                match in_flight.get_mut(&key) {
+                    Some(waiters) => {
+                        let (tx, rx) = oneshot::channel();
+                        waiters.push(tx);
+                        Some(rx)
+                    }
+                    None => {
+                        in_flight.insert(key.clone(), Vec::new());
+                        None
+                    }
+                }
+            };
+
+            match waiting {
+                Some(rx) => match rx.await {
+                    Ok(r) => return r.map_err(|err| RepositoryError::SingleFlight(err).into()),
+                    // 먼저 시작한 요청이 취소됨
+                    Err(_) => continue,
+                },
+                None => break,
+            }
+        }
+
+        let leader = Leader {
+            in_flight: &self.in_flight,
+            key: Some(key),
+        };
+
+        let r = f().await;
+
+        let waiters = leader.finish();
+
+        if waiters.is_empty() {
+            return r;
+        }
+
+        let r = r.map_err(Arc::new);
+
+        for tx in waiters {
+            let _ = tx.send(r.clone());
+        }
+
+        r.map_err(|err| RepositoryError::SingleFlight(err).into())
+    }
+}
+
+/// 먼저 시작한 요청이 끝나기 전에 취소되어도 키를 지워서 기다리던 요청이 이어서 실행하게 함
+struct Leader<'a, K: Hash + Eq, V> {
+    in_flight: &'a Mutex<HashMap<K, Vec<oneshot::Sender<Shared<V>>>>>,
+    key: Option<K>,
+}
+
+impl<'a, K: Hash + Eq, V> Leader<'a, K, V> {
+    fn finish(mut self) -> Vec<oneshot::Sender<Shared<V>>> {
+        let key = self.key.take().unwrap();
+
+        self.in_flight.lock().remove(&key).unwrap_or_default()
+    }
+}
+
+impl<'a, K: Hash + Eq, V> Drop for Leader<'a, K, V> {
+    fn drop(&mut self) {
+        if let Some(key) = self.key.take() {
+            self.in_flight.lock().remove(&key);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::atomic::{AtomicUsize, Ordering},
+        time::Duration,
+    };
+
+    use sea_orm::DbErr;
+
+    use super::*;
+
+    fn db_err(message: &str) -> crate::Error {
+        DbErr::Custom(message.to_string()).into()
+    }
+
+    #[tokio::test]
+    async fn concurrent_calls_share_one_result() {
+        let group = &SingleFlight::<u32, u32>::default();
+        let calls = &AtomicUsize::new(0);
+
+        let run = |value| {
+            group.run(1, move || async move {
+                calls.fetch_add(1, Ordering::SeqCst);
+                tokio::time::sleep(Duration::from_millis(10)).await;
+                Ok(value)
+            })
+        };
+
+        let (a, b, c) = tokio::join!(run(1), run(2), run(3));
+        let (a, b, c) = (a.unwrap(), b.unwrap(), c.unwrap());
+
+        assert_eq!((a, b), (c, c));
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        assert!(group.in_flight.lock().is_empty());
+
+        // 끝난 뒤에 들어온 요청은 다시 실행함
+        assert_eq!(run(4).await.unwrap(), 4);
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn different_keys_run_separately() {
+        let group = &SingleFlight::<u32, u32>::default();
+        let calls = &AtomicUsize::new(0);
+
+        let run = |key| {
+            group.run(key, move || async move {
+                calls.fetch_add(1, Ordering::SeqCst);
+                tokio::time::sleep(Duration::from_millis(10)).await;
+                Ok(key)
+            })
+        };
+
+        let (a, b) = tokio::join!(run(1), run(2));
+
+        assert_eq!((a.unwrap(), b.unwrap()), (1, 2));
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn waiters_share_leader_error() {
+        let group = &SingleFlight::<u32, u32>::default();
+        let calls = &AtomicUsize::new(0);
+
+        let run = || {
+            group.run(1, move || async move {
+                calls.fetch_add(1, Ordering::SeqCst);
+                tokio::time::sleep(Duration::from_millis(10)).await;
+                Err(db_err("leader failed"))
+            })
+        };
+
+        let (a, b) = tokio::join!(run(), run());
+
+        for r in [a, b] {
+            assert!(r.unwrap_err().to_string().contains("leader failed"));
+        }
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        assert!(group.in_flight.lock().is_empty());
+    }
+
+    #[tokio::test]
+    async fn waiter_takes_over_cancelled_leader() {
+        let group = &SingleFlight::<u32, u32>::default();
+        let calls = &AtomicUsize::new(0);
+
+        let run = |value, delay| {
+            group.run(1, move || async move {
+                calls.fetch_add(1, Ordering::SeqCst);
+                tokio::time::sleep(delay).await;
+                Ok(value)
+            })
+        };
+
+        let leader =
+            tokio::time::timeout(Duration::from_millis(10), run(1, Duration::from_secs(60)));
+        let waiters = async {
+            tokio::join!(
+                run(2, Duration::from_millis(10)),
+                run(3, Duration::from_millis(10))
+            )
+        };
+
+        let (leader, (a, b)) = tokio::join!(leader, waiters);
+
+        assert!(leader.is_err());
+        // 기다리던 요청 중 하나가 이어서 실행하고, 나머지는 그 결과를 같이 받음
+        let (a, b) = (a.unwrap(), b.unwrap());
+        assert_eq!(a, b);
+        assert!([2, 3].contains(&a));
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+        assert!(group.in_flight.lock().is_empty());
+    }
+}
@@ -0,0 +1,6 @@
+mod book;
+mod group;
+
+pub use book::*;
+
+use group::SingleFlight;
@@ -0,0 +1,1683 @@
+use chrono::{DateTime, Utc};
+use itertools::Itertools;
+use sea_orm::{
+    ConnectionTrait, DatabaseConnection, DbBackend, DbErr, EntityTrait, IdenStatic, QueryResult,
+    Statement, Value,
+};
+
+use crate::{
+    database::postgresql::entity::{book, book_tag, tag_stat},
+    entity::{
+        Book, BookFacets, BookFilter, BookGroupByTag, BookSortBy, BookTag, BookTagKind,
+        BookTagSortBy, BookTagStatDrift, BookTagSummary, Sort,
+    },
+    repository::{r#trait::BOOKS_BY_TAG_LIMIT, random_key_of},
+};
+
+use super::placeholder;
+
+pub async fn get_one(db: &DatabaseConnection, book_id: u32) -> crate::Result<Option<Book>> {
+    let backend = db.get_database_backend();
+    let (query, values) = select_books_sql(backend, SelectBy::Id(book_id), true);
+
+    let stmt = Statement::from_sql_and_values(backend, &query, values);
+
+    let query_results = db.query_all(stmt).await?;
+
+    let books = into_books(query_results)?;
+
+    Ok(books.into_iter().next())
+}
+
+pub async fn get_many(
+    db: &DatabaseConnection,
+    filter: BookFilter,
+    per_page: usize,
+    page: usize,
+    sort_by: BookSortBy,
+    include_tags: bool,
+) -> crate::Result<Vec<Book>> {
+    let backend = db.get_database_backend();
+    let (query, values) = select_books_sql(
+        backend,
+        SelectBy::Many(filter, per_page, page, sort_by),
+        include_tags,
+    );
+
+    let stmt = Statement::from_sql_and_values(backend, &query, values);
+
+    let query_results = db.query_all(stmt).await?;
+
+    let books = into_books(query_results)?;
+
+    Ok(books)
+}
+
+pub async fn count(db: &DatabaseConnection, filter: BookFilter) -> crate::Result<usize> {
+    let books = book::Entity.as_str();
+
+    let backend = db.get_database_backend();
+    let mut values = Vec::new();
+    let where_ = where_sql(&filter_sql(backend, &filter, &mut values));
+
+    let query = format!(r#"SELECT COUNT(*) AS "count" FROM "{books}" {where_}"#);
+
+    let stmt = Statement::from_sql_and_values(backend, &query, values);
+
+    let count = match db.query_one(stmt).await? {
+        Some(res) => res.try_get::<i64>("", "count")?,
+        None => 0,
+    };
+
+    Ok(count as usize)
+}
+
+pub async fn get_facets(
+    db: &DatabaseConnection,
+    filter: BookFilter,
+    tag_limit: usize,
+) -> crate::Result<BookFacets> {
+    let backend = db.get_database_backend();
+
+    let count_by = |column: &'static str| {
+        let (query, values) = select_facet_sql(backend, &filter, column);
+
+        db.query_all(Statement::from_sql_and_values(backend, &query, values))
+    };
+
+    let (query, values) = select_tag_facets_sql(backend, &filter, tag_limit);
+    let count_by_tag = db.query_all(Statement::from_sql_and_values(backend, &query, values));
+
+    let (kinds, languages, tags) = tokio::try_join!(
+        count_by(book::Column::Kind.as_str()),
+        count_by(book::Column::Language.as_str()),
+        count_by_tag
+    )?;
+
+    let kinds = kinds
+        .into_iter()
+        .map(|res| {
+            let kind = res.try_get::<String>("", "value")?;
+            let count = res.try_get::<i64>("", "count")?;
+
+            Ok((kind.into(), count as usize))
+        })
+        .collect::<Result<_, DbErr>>()?;
+
+    let languages = languages
+        .into_iter()
+        .map(|res| {
+            let language = res.try_get::<String>("", "value")?;
+            let count = res.try_get::<i64>("", "count")?;
+
+            Ok((language, count as usize))
+        })
+        .collect::<Result<_, DbErr>>()?;
+
+    let tags = tags
+        .into_iter()
+        .map(|res| {
+            let tag = book_tag::Model {
+                id: res.try_get::<i64>("", "id")?,
+                kind: res.try_get::<String>("", "kind")?,
+                name: res.try_get::<String>("", "name")?,
+            };
+            let count = res.try_get::<i64>("", "count")?;
+
+            Ok((tag.into(), count as usize))
+        })
+        .collect::<Result<_, DbErr>>()?;
+
+    Ok(BookFacets {
+        kinds,
+        languages,
+        tags,
+    })
+}
+
+pub async fn get_many_by_ids(
+    db: &DatabaseConnection,
+    book_ids: Vec<u32>,
+    include_tags: bool,
+) -> crate::Result<Vec<Book>> {
+    select_books(db, SelectBy::Ids(book_ids), include_tags).await
+}
+
+pub async fn get_many_by_ids_unordered(
+    db: &DatabaseConnection,
+    book_ids: Vec<u32>,
+    include_tags: bool,
+) -> crate::Result<Vec<Book>> {
+    select_books(db, SelectBy::IdsUnordered(book_ids), include_tags).await
+}
+
+async fn select_books(
+    db: &DatabaseConnection,
+    select_by: SelectBy,
+    include_tags: bool,
+) -> crate::Result<Vec<Book>> {
+    let backend = db.get_database_backend();
+    let (query, values) = select_books_sql(backend, select_by, include_tags);
+
+    let stmt = Statement::from_sql_and_values(backend, &query, values);
+
+    let query_results = db.query_all(stmt).await?;
+
+    let books = into_books(query_results)?;
+
+    Ok(books)
+}
+
+pub async fn get_tags(
+    db: &DatabaseConnection,
+    kind: BookTagKind,
+    per_page: usize,
+    page: usize,
+    sort_by: BookTagSortBy,
+) -> crate::Result<Vec<BookTagSummary>> {
+    let backend = db.get_database_backend();
+    let (query, values) = select_tags_sql(backend, kind, per_page, page, sort_by);
+
+    let stmt = Statement::from_sql_and_values(backend, &query, values);
+
+    let query_results = db.query_all(stmt).await?;
+
+    let tags = query_results
+        .into_iter()
+        .map(|res| {
+            let tag = book_tag::Model {
+                id: res.try_get::<i64>("", "id")?,
+                kind: res.try_get::<String>("", "kind")?,
+                name: res.try_get::<String>("", "name")?,
+            };
+
+            Ok(BookTagSummary {
+                tag: tag.into(),
+                book_count: res.try_get::<i64>("", "book_count")? as usize,
+                latest_created_at: res.try_get::<DateTime<Utc>>("", "latest_created_at")?,
+            })
+        })
+        .collect::<Result<_, DbErr>>()?;
+
+    Ok(tags)
+}
+
+pub async fn get_many_by_tags(
+    db: &DatabaseConnection,
+    book_tags: Vec<BookTag>,
+) -> crate::Result<Vec<BookGroupByTag>> {
+    let groups = book_tags.into_iter().map(|tag| async move {
+        let books = get_many_by_tag(db, tag.clone()).await?;
+
+        crate::Result::Ok(BookGroupByTag { tag, books })
+    });
+
+    futures::future::try_join_all(groups).await
+}
+
+pub async fn get_many_by_tag(
+    db: &DatabaseConnection,
+    book_tag: BookTag,
+) -> crate::Result<Vec<Book>> {
+    let filter = BookFilter {
+        tags: vec![book_tag],
+        ..Default::default()
+    };
+
+    let backend = db.get_database_backend();
+    let (query, values) = select_books_sql(
+        backend,
+        SelectBy::Many(filter, BOOKS_BY_TAG_LIMIT, 1, BookSortBy::Id(Sort::Desc)),
+        true,
+    );
+
+    let stmt = Statement::from_sql_and_values(backend, &query, values);
+
+    let query_results = db.query_all(stmt).await?;
+
+    let books = into_books(query_results)?;
+
+    Ok(books)
+}
+
+/// 작품과 태그를 insert하고 연결함, 트랜잭션 안에서 실행해야 함
+pub async fn insert_book(txn: &impl ConnectionTrait, book: Book) -> Result<(), DbErr> {
+    let (book_id, book_kind, language, created_at) = (
+        book.id,
+        book.kind.as_str().to_string(),
+        book.language.clone(),
+        book.created_at,
+    );
+
+    // 같은 태그가 여러번 붙어있어도 하나만 연결함
+    let book_tags = book.tags.iter().cloned().unique().collect::<Vec<_>>();
+
+    let book_tag_ids = insert_book_tags(book_tags, txn).await?;
+
+    book::Entity::insert::<book::ActiveModel>(
+        Book {
+            tags: Vec::new(),
+            ..book
+        }
+        .into(),
+    )
+    .exec(txn)
+    .await?;
+
+    // 값이 없는 insert_many는 잘못된 쿼리를 만듦
+    if !book_tag_ids.is_empty() {
+        book::tag_ref::Entity::insert_many(
+            book_tag_ids
+                .into_iter()
+                .map(|tag_id| book::tag_ref::ActiveModel::insert(book_id as i64, tag_id)),
+        )
+        .exec(txn)
+        .await?;
+
+        // 어긋난 통계는 `recompute_tag_stats`로 다시 맞춤
+        let (query, values) = upsert_tag_stats_sql(
+            txn.get_database_backend(),
+            book_id,
+            book_kind,
+            language,
+            created_at,
+        );
+
+        txn.execute(Statement::from_sql_and_values(
+            txn.get_database_backend(),
+            &query,
+            values,
+        ))
+        .await?;
+    }
+
+    Ok(())
+}
+
+/// `"tag_stats"`를 `"books_tag_ref"`에서 다시 셈, 트랜잭션 안에서 실행해야 함
+///
+/// 저장되어 있던 값과 다시 센 값이 다른 항목들을 반환함
+pub async fn recompute_tag_stats(
+    txn: &impl ConnectionTrait,
+) -> Result<Vec<BookTagStatDrift>, DbErr> {
+    let backend = txn.get_database_backend();
+    let tag_stats = tag_stat::Entity.as_str();
+
+    // READ COMMITTED에서는 문장마다 다른 시점을 읽기 때문에, 읽고 다시 쓰는 동안 작품 추가가 `"tag_stats"`를 바꾸지 못하게 막음
+    // 작품 추가는 `"tag_stats"`를 바꾸기 전에 기다리고, 읽기는 막지 않음
+    // sqlite는 쓰기가 하나씩만 실행되고 트랜잭션 안에서는 같은 시점을 읽음
+    //
+    // 모든 작품을 다시 세기 때문에 이 트랜잭션에서만 `statement_timeout`을 없앰
+    if backend == DbBackend::Postgres {
+        for query in [
+            "SET LOCAL statement_timeout = 0".to_string(),
+            format!(r#"LOCK TABLE "{tag_stats}" IN EXCLUSIVE MODE"#),
+        ] {
+            txn.execute(Statement::from_string(backend, query)).await?;
+        }
+    }
+
+    let drifts = txn
+        .query_all(Statement::from_string(
+            backend,
+            tag_stat_drifts_sql(backend),
+        ))
+        .await?
+        .into_iter()
+        .map(|res| {
+            let tag = book_tag::Model {
+                id: res.try_get::<i64>("", "id")?,
+                kind: res.try_get::<String>("", "kind")?,
+                name: res.try_get::<String>("", "name")?,
+            };
+
+            Ok(BookTagStatDrift {
+                tag: tag.into(),
+                book_kind: res.try_get::<String>("", "book_kind")?.into(),
+                language: res.try_get::<String>("", "language")?,
+                stored: res.try_get::<i64>("", "stored")? as usize,
+                actual: res.try_get::<i64>("", "actual")? as usize,
+            })
+        })
+        .collect::<Result<Vec<_>, DbErr>>()?;
+
+    for query in [
+        format!(r#"DELETE FROM "{tag_stats}""#),
+        format!(
+            r#"
+            INSERT INTO "{tag_stats}"
+                ("book_tag_id", "book_kind", "language", "book_count", "latest_created_at")
+            {counted}
+            "#,
+            counted = count_tag_stats_sql()
+        ),
+    ] {
+        txn.execute(Statement::from_string(backend, query)).await?;
+    }
+
+    Ok(drifts)
+}
+
+/// 작품 하나에 붙은 태그들의 통계를 1씩 늘림
+///
+/// 1번째 = book_id, 2번째 = 작품 종류, 3번째 = 언어, 4번째 = 추가된 시각
+fn upsert_tag_stats_sql(
+    backend: DbBackend,
+    book_id: u32,
+    book_kind: String,
+    language: String,
+    created_at: DateTime<Utc>,
+) -> (String, Vec<Value>) {
+    let tag_stats = tag_stat::Entity.as_str();
+    let books_tag_ref = book::tag_ref::Entity.as_str();
+
+    let var = |n: usize| placeholder(backend, n);
+    let (book_id_var, book_kind_var, language_var, created_at_var) =
+        (var(1), var(2), var(3), var(4));
+
+    // sqlite에서는 인자가 두개인 MAX가 GREATEST와 같음
+    let greatest = match backend {
+        DbBackend::Sqlite => "MAX",
+        _ => "GREATEST",
+    };
+
+    let query = format!(
+        r#"
+        INSERT INTO "{tag_stats}"
+            ("book_tag_id", "book_kind", "language", "book_count", "latest_created_at")
+        SELECT
+            "{books_tag_ref}"."book_tag_id",
+            {book_kind_var},
+            {language_var},
+            1,
+            {created_at_var}
+        FROM
+            "{books_tag_ref}"
+        WHERE
+            "{books_tag_ref}"."book_id" = {book_id_var}
+        ON CONFLICT ("book_tag_id", "book_kind", "language")
+            DO UPDATE SET
+                "book_count" = "{tag_stats}"."book_count" + 1,
+                "latest_created_at" = {greatest}(
+                    "{tag_stats}"."latest_created_at",
+                    EXCLUDED."latest_created_at"
+                )
+        "#
+    );
+
+    let values = vec![
+        (book_id as i64).into(),
+        book_kind.into(),
+        language.into(),
+        created_at.into(),
+    ];
+
+    (query, values)
+}
+
+/// `"tag_stats"`와 같은 형태로 `"books_tag_ref"`를 셈
+fn count_tag_stats_sql() -> String {
+    let books = book::Entity.as_str();
+    let books_tag_ref = book::tag_ref::Entity.as_str();
+
+    format!(
+        r#"
+        SELECT
+            "{books_tag_ref}"."book_tag_id" AS "book_tag_id",
+            "{books}"."kind" AS "book_kind",
+            "{books}"."language" AS "language",
+            COUNT(*) AS "book_count",
+            MAX("{books}"."created_at") AS "latest_created_at"
+        FROM
+            "{books_tag_ref}"
+        INNER JOIN "{books}"
+            ON "{books}"."id" = "{books_tag_ref}"."book_id"
+        GROUP BY
+            "{books_tag_ref}"."book_tag_id", "{books}"."kind", "{books}"."language"
+        "#
+    )
+}
+
+/// 저장된 `"tag_stats"`와 다시 센 값이 다른 항목들, 한쪽에만 있으면 다른 쪽은 0
+fn tag_stat_drifts_sql(backend: DbBackend) -> String {
+    let tag_stats = tag_stat::Entity.as_str();
+    let book_tags = book_tag::Entity.as_str();
+    let counted = count_tag_stats_sql();
+
+    let same_key = r#"
+        "counted"."book_tag_id" = "stored"."book_tag_id"
+            AND "counted"."book_kind" = "stored"."book_kind"
+            AND "counted"."language" = "stored"."language"
+    "#;
+
+    let compared = match backend {
+        // sqlite는 3.39부터 FULL OUTER JOIN을 지원해서 LEFT JOIN 두개로 나눔
+        DbBackend::Sqlite => format!(
+            r#"
+            SELECT
+                "stored"."book_tag_id", "stored"."book_kind", "stored"."language",
+                "stored"."book_count" AS "stored",
+                COALESCE("counted"."book_count", 0) AS "actual"
+            FROM
+                "{tag_stats}" AS "stored"
+            LEFT JOIN ({counted}) AS "counted"
+                ON {same_key}
+            UNION ALL
+            SELECT
+                "counted"."book_tag_id", "counted"."book_kind", "counted"."language",
+                0 AS "stored",
+                "counted"."book_count" AS "actual"
+            FROM
+                ({counted}) AS "counted"
+            LEFT JOIN "{tag_stats}" AS "stored"
+                ON {same_key}
+            WHERE
+                "stored"."book_tag_id" IS NULL
+            "#
+        ),
+        _ => format!(
+            r#"
+            SELECT
+                COALESCE("stored"."book_tag_id", "counted"."book_tag_id") AS "book_tag_id",
+                COALESCE("stored"."book_kind", "counted"."book_kind") AS "book_kind",
+                COALESCE("stored"."language", "counted"."language") AS "language",
+                COALESCE("stored"."book_count", 0) AS "stored",
+                COALESCE("counted"."book_count", 0) AS "actual"
+            FROM
+                "{tag_stats}" AS "stored"
+            FULL OUTER JOIN ({counted}) AS "counted"
+                ON {same_key}
+            "#
+        ),
+    };
+
+    format!(
+        r#"
+        SELECT
+            "{book_tags}"."id", "{book_tags}"."kind", "{book_tags}"."name",
+            "compared"."book_kind", "compared"."language",
+            CAST("compared"."stored" AS BIGINT) AS "stored",
+            CAST("compared"."actual" AS BIGINT) AS "actual"
+        FROM
+            ({compared}) AS "compared"
+        INNER JOIN "{book_tags}"
+            ON "{book_tags}"."id" = "compared"."book_tag_id"
+        WHERE
+            "compared"."stored" <> "compared"."actual"
+        ORDER BY
+            "{book_tags}"."id", "compared"."book_kind", "compared"."language"
+        "#
+    )
+}
+
+/// 이미 있는 태그를 포함해서 모든 태그의 id를 반환함
+async fn insert_book_tags(
+    book_tags: Vec<BookTag>,
+    db: &impl ConnectionTrait,
+) -> Result<Vec<i64>, DbErr> {
+    if book_tags.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let backend = db.get_database_backend();
+    let book_tags_table = book_tag::Entity.as_str();
+
+    let vars = (1..=book_tags.len())
+        .map(|i| i * 2) // i * column count
+        .map(|i| {
+            // "($1, $2)"
+            format!(
+                "({}, {})",
+                placeholder(backend, i - 1),
+                placeholder(backend, i)
+            )
+        })
+        .join(",");
+
+    let values = book_tags
+        .iter()
+        .flat_map(|x| vec![x.kind().into(), x.name().into()])
+        .collect::<Vec<Value>>();
+
+    // `DO NOTHING`은 이미 있던 태그의 id를 반환하지 않기 때문에 아무것도 바꾸지 않는 update를 함
+    let insert_book_tags_sql = format!(
+        r#"
+        INSERT INTO
+            "{book_tags_table}" ("kind", "name")
+        VALUES
+            {vars}
+        ON CONFLICT ("kind", "name")
+            DO UPDATE SET "name" = EXCLUDED."name"
+        RETURNING "id"
+        "#
+    );
+
+    let res = db
+        .query_all(Statement::from_sql_and_values(
+            backend,
+            &insert_book_tags_sql,
+            values,
+        ))
+        .await?;
+
+    res.into_iter()
+        .map(|x| x.try_get("", book_tag::Column::Id.as_str()))
+        .collect()
+}
+
+/// 작품마다 한 행씩 읽고, `"B_tags"`에 모아둔 태그는 `[[id, kind, name], ..]` json으로 읽음
+pub fn into_books(query_results: Vec<QueryResult>) -> Result<Vec<Book>, DbErr> {
+    query_results
+        .into_iter()
+        .map(|res| {
+            let book = book::Model {
+                id: res.try_get::<i64>("", "A_id")?,
+                title: res.try_get::<String>("", "A_title")?,
+                page: res.try_get::<i32>("", "A_page")?,
+                language: res.try_get::<String>("", "A_language")?,
+                kind: res.try_get::<String>("", "A_kind")?,
+                created_at: res.try_get::<DateTime<Utc>>("", "A_created_at")?,
+            };
+
+            let book_tags = res.try_get::<String>("", "B_tags")?;
+            let book_tags = serde_json::from_str::<Vec<(i64, String, String)>>(&book_tags)
+                .map_err(|err| DbErr::Custom(format!("B_tags: {err}")))?
+                .into_iter()
+                .map(|(id, kind, name)| book_tag::Model { id, kind, name })
+                .collect::<Vec<_>>();
+
+            Ok((book, book_tags).into())
+        })
+        .collect()
+}
+
+pub enum SelectBy {
+    /// 요청한 순서대로
+    Ids(Vec<u32>),
+    /// 순서를 맞추지 않음, id가 많으면 `Ids`의 `ORDER BY`가 커지기 때문에 호출하는 쪽에서 정렬할 때 사용함
+    IdsUnordered(Vec<u32>),
+    Id(u32),
+    /// filter, per_page, page, sort_by
+    Many(BookFilter, usize, usize, BookSortBy),
+}
+
+pub fn select_books_sql(
+    backend: DbBackend,
+    select_by: SelectBy,
+    include_tags: bool,
+) -> (String, Vec<Value>) {
+    let books = book::Entity.as_str();
+
+    let var = |n: usize| placeholder(backend, n);
+
+    let (mut where_, mut offset, mut limit, mut order_by, mut last_order_by, mut values) =
+        <(String, String, String, String, String, Vec<Value>)>::default();
+    let mut inner = None;
+
+    let ordered = !matches!(select_by, SelectBy::IdsUnordered(_));
+
+    match select_by {
+        SelectBy::Many(filter, per_page, page, sort_by) => {
+            offset = format!("OFFSET {}", var(1));
+            limit = format!("LIMIT {}", var(2));
+
+            values = vec![
+                ((per_page * (page - 1)) as u64).into(),
+                (per_page as u64).into(),
+            ];
+
+            if let BookSortBy::Random(seed) = sort_by {
+                // 3번째 = 시작 지점, 4번째 = 각 구간에서 가져올 최대 갯수
+                values.push(random_key_of(seed).into());
+                values.push(((per_page * page) as u64).into());
+            }
+
+            let conditions = filter_sql(backend, &filter, &mut values);
+
+            match sort_by {
+                BookSortBy::Id(sort) => {
+                    let sort = match sort {
+                        Sort::Desc => "DESC",
+                        Sort::Asc => "ASC",
+                    };
+
+                    order_by = format!(r#"ORDER BY "{books}"."id" {sort}"#);
+                    last_order_by = order_by.clone();
+
+                    where_ = where_sql(&conditions);
+                }
+                BookSortBy::Random(_) => {
+                    let random_key = book::RANDOM_KEY;
+
+                    inner = Some(select_random_books_sql(backend, &conditions));
+
+                    last_order_by = format!(
+                        r#"ORDER BY "{books}"."wrapped", "{books}"."{random_key}", "{books}"."id""#
+                    );
+                }
+            }
+        }
+        // `IN ()`와 비어있는 `ORDER BY`는 문법 오류임
+        SelectBy::Ids(book_ids) | SelectBy::IdsUnordered(book_ids) if book_ids.is_empty() => {
+            where_ = "WHERE 1 = 0".to_string();
+        }
+        SelectBy::Ids(book_ids) | SelectBy::IdsUnordered(book_ids) => {
+            let (vars, vals): (Vec<_>, Vec<_>) = book_ids
+                .into_iter()
+                .enumerate()
+                .map(|(i, x)| (var(i + 1), x))
+                .unzip();
+
+            where_ = format!(r#"WHERE "{books}"."id" IN ({vars})"#, vars = vars.join(","));
+
+            if ordered {
+                last_order_by = format!(
+                    r#"ORDER BY {vars}"#,
+                    vars = vars
+                        .iter()
+                        .map(|var| format!(r#""{books}"."id" = {var} DESC"#))
+                        // .rev()
+                        .join(",")
+                );
+            }
+
+            values = vals.into_iter().map_into().collect();
+        }
+        SelectBy::Id(book_id) => {
+            where_ = format!(r#"WHERE "{books}"."id" = {}"#, var(1));
+
+            values = vec![book_id.into()];
+        }
+    };
+
+    // subquery의 순서는 바깥 쿼리에서 유지되지 않기 때문에 `last_order_by`로 다시 정렬함
+    //
+    // sqlite는 `LIMIT`이 `OFFSET`보다 먼저 와야 함
+    let inner = inner.unwrap_or_else(|| {
+        format!(r#"SELECT * FROM "{books}" {where_} {order_by} {limit} {offset}"#)
+    });
+
+    // 태그가 필요없으면 태그가 없는 작품처럼 읽음
+    let tags = if include_tags {
+        select_book_tags_json_sql(backend)
+    } else {
+        "CAST('[]' AS TEXT)".to_string()
+    };
+
+    let query = format!(
+        r#"
+        SELECT
+            "{books}"."id" AS "A_id",
+            "{books}"."title" AS "A_title",
+            "{books}"."page" AS "A_page",
+            "{books}"."language" AS "A_language",
+            "{books}"."kind" AS "A_kind",
+            "{books}"."created_at" AS "A_created_at",
+            {tags} AS "B_tags"
+        FROM
+            ({inner}) AS "{books}"
+        {last_order_by}
+        "#
+    );
+
+    log::debug!("values = {values:?}");
+
+    (query, values)
+}
+
+/// 바깥 쿼리의 `"books"`에 붙은 태그들을 `[[id, kind, name], ..]` json 문자열 하나로 모음
+///
+/// 태그가 없으면 `[]`
+fn select_book_tags_json_sql(backend: DbBackend) -> String {
+    let books = book::Entity.as_str();
+    let book_tags = book_tag::Entity.as_str();
+    let books_tag_ref = book::tag_ref::Entity.as_str();
+
+    let tag = match backend {
+        DbBackend::Sqlite => "json_array",
+        _ => "json_build_array",
+    };
+
+    let tags = match backend {
+        // sqlite의 json_group_array는 정렬할 수 없어서 subquery의 순서를 따름
+        DbBackend::Sqlite => r#"json_group_array(json("tag"))"#,
+        // 태그가 없으면 json_agg는 NULL임
+        _ => r#"COALESCE(json_agg("tag" ORDER BY "id")::TEXT, '[]')"#,
+    };
+
+    format!(
+        r#"
+        (
+            SELECT {tags} FROM (
+                SELECT
+                    "{book_tags}"."id" AS "id",
+                    {tag}(
+                        "{book_tags}"."id",
+                        "{book_tags}"."kind",
+                        "{book_tags}"."name"
+                    ) AS "tag"
+                FROM
+                    "{books_tag_ref}"
+                INNER JOIN "{book_tags}"
+                    ON "{book_tags}"."id" = "{books_tag_ref}"."book_tag_id"
+                WHERE
+                    "{books_tag_ref}"."book_id" = "{books}"."id"
+                ORDER BY
+                    "{book_tags}"."id"
+            ) AS "tags"
+        )
+        "#
+    )
+}
+
+/// `ORDER BY RANDOM()`은 매번 테이블 전체를 정렬하고, 페이지마다 순서가 달라짐
+///
+/// 대신 작품마다 미리 정해둔 `random_key`를 seed로 정한 시작 지점부터 인덱스 순서대로 읽고,
+/// 끝에 도달하면 처음부터 다시 읽음
+///
+/// 각 구간에서는 `OFFSET + LIMIT`(4번째)개까지만 가져오기 때문에 전체를 정렬하지 않음
+///
+/// 1번째 = offset, 2번째 = limit, 3번째 = 시작 지점
+///
+/// sqlite는 `UNION ALL`의 각 구간에 괄호와 `ORDER BY`를 쓸 수 없어서 subquery로 감쌈
+fn select_random_books_sql(backend: DbBackend, conditions: &[String]) -> String {
+    let books = book::Entity.as_str();
+    let random_key = book::RANDOM_KEY;
+
+    let var = |n: usize| placeholder(backend, n);
+    let (offset, limit, start, each_limit) = (var(1), var(2), var(3), var(4));
+
+    let and = conditions
+        .iter()
+        .map(|condition| format!("AND {condition}"))
+        .join(" ");
+
+    format!(
+        r#"
+        SELECT * FROM (
+            SELECT * FROM (
+                SELECT *, 0 AS "wrapped" FROM "{books}"
+                WHERE "{books}"."{random_key}" >= {start} {and}
+                ORDER BY "{books}"."{random_key}", "{books}"."id"
+                LIMIT {each_limit}
+            ) AS "after_start"
+            UNION ALL
+            SELECT * FROM (
+                SELECT *, 1 AS "wrapped" FROM "{books}"
+                WHERE "{books}"."{random_key}" < {start} {and}
+                ORDER BY "{books}"."{random_key}", "{books}"."id"
+                LIMIT {each_limit}
+            ) AS "before_start"
+        ) AS "{books}"
+        ORDER BY "wrapped", "{random_key}", "id"
+        LIMIT {limit}
+        OFFSET {offset}
+        "#
+    )
+}
+
+/// 필터에 맞는 작품들을 `"books"."{column}"`으로 묶어서 셈
+fn select_facet_sql(backend: DbBackend, filter: &BookFilter, column: &str) -> (String, Vec<Value>) {
+    let books = book::Entity.as_str();
+
+    let mut values = Vec::new();
+    let where_ = where_sql(&filter_sql(backend, filter, &mut values));
+
+    let query = format!(
+        r#"
+        SELECT
+            "{books}"."{column}" AS "value",
+            COUNT(*) AS "count"
+        FROM
+            "{books}"
+        {where_}
+        GROUP BY
+            "{books}"."{column}"
+        ORDER BY
+            "count" DESC, "value" ASC
+        "#
+    );
+
+    (query, values)
+}
+
+/// 필터에 맞는 작품들의 태그를 태그 종류마다 작품 수가 많은 순서대로 `tag_limit`개씩 가져옴
+///
+/// 작품 종류와 언어로만 필터링하면 `"books_tag_ref"` 대신 미리 세어둔 `"tag_stats"`를 합침
+fn select_tag_facets_sql(
+    backend: DbBackend,
+    filter: &BookFilter,
+    tag_limit: usize,
+) -> (String, Vec<Value>) {
+    let books = book::Entity.as_str();
+    let book_tags = book_tag::Entity.as_str();
+    let books_tag_ref = book::tag_ref::Entity.as_str();
+    let tag_stats = tag_stat::Entity.as_str();
+
+    let mut values = Vec::new();
+
+    let (from, count, where_) = if is_counted_by_tag_stats(filter) {
+        let mut bind = |value: Value| {
+            values.push(value);
+            placeholder(backend, values.len())
+        };
+
+        let mut conditions = Vec::new();
+
+        if !filter.kinds.is_empty() {
+            let vars = filter
+                .kinds
+                .iter()
+                .map(|kind| bind(kind.as_str().into()))
+                .join(",");
+
+            conditions.push(format!(r#""{tag_stats}"."book_kind" IN ({vars})"#));
+        }
+
+        if !filter.languages.is_empty() {
+            let vars = filter
+                .languages
+                .iter()
+                .map(|language| bind(language.as_str().into()))
+                .join(",");
+
+            conditions.push(format!(r#""{tag_stats}"."language" IN ({vars})"#));
+        }
+
+        (
+            format!(
+                r#"
+                "{tag_stats}"
+                INNER JOIN "{book_tags}"
+                    ON "{book_tags}"."id" = "{tag_stats}"."book_tag_id"
+                "#
+            ),
+            format!(r#"CAST(SUM("{tag_stats}"."book_count") AS BIGINT)"#),
+            where_sql(&conditions),
+        )
+    } else {
+        (
+            format!(
+                r#"
+                "{books}"
+                INNER JOIN "{books_tag_ref}"
+                    ON "{books_tag_ref}"."book_id" = "{books}"."id"
+                INNER JOIN "{book_tags}"
+                    ON "{book_tags}"."id" = "{books_tag_ref}"."book_tag_id"
+                "#
+            ),
+            "COUNT(*)".to_string(),
+            where_sql(&filter_sql(backend, filter, &mut values)),
+        )
+    };
+
+    values.push((tag_limit as u64).into());
+    let tag_limit = placeholder(backend, values.len());
+
+    let query = format!(
+        r#"
+        SELECT
+            "id", "kind", "name", "count"
+        FROM (
+            SELECT
+                "{book_tags}"."id",
+                "{book_tags}"."kind",
+                "{book_tags}"."name",
+                {count} AS "count",
+                ROW_NUMBER() OVER (
+                    PARTITION BY "{book_tags}"."kind"
+                    ORDER BY {count} DESC, "{book_tags}"."name" ASC
+                ) AS "rank"
+            FROM
+                {from}
+            {where_}
+            GROUP BY
+                "{book_tags}"."id", "{book_tags}"."kind", "{book_tags}"."name"
+        ) AS "facets"
+        WHERE
+            "rank" <= {tag_limit} AND "count" > 0
+        ORDER BY
+            "kind" ASC, "rank" ASC
+        "#
+    );
+
+    (query, values)
+}
+
+/// 작품 종류와 언어 외의 조건이 없으면 `"tag_stats"`로 셀 수 있음
+fn is_counted_by_tag_stats(filter: &BookFilter) -> bool {
+    let BookFilter {
+        kinds: _,
+        languages: _,
+        min_page,
+        max_page,
+        created_after,
+        created_before,
+        after_id,
+        tags,
+        exclude_tags,
+    } = filter;
+
+    min_page.is_none()
+        && max_page.is_none()
+        && created_after.is_none()
+        && created_before.is_none()
+        && after_id.is_none()
+        && tags.is_empty()
+        && exclude_tags.is_empty()
+}
+
+/// `kind` 태그들을 작품 수와 가장 최근에 추가된 작품의 시각과 같이 가져옴
+///
+/// `"books_tag_ref"` 대신 미리 세어둔 `"tag_stats"`를 작품 종류와 언어에 상관없이 합침
+///
+/// 작품이 하나도 없는 태그는 포함하지 않음
+fn select_tags_sql(
+    backend: DbBackend,
+    kind: BookTagKind,
+    per_page: usize,
+    page: usize,
+    sort_by: BookTagSortBy,
+) -> (String, Vec<Value>) {
+    let book_tags = book_tag::Entity.as_str();
+    let tag_stats = tag_stat::Entity.as_str();
+
+    let var = |n: usize| placeholder(backend, n);
+    let (offset, limit, kind_var) = (var(1), var(2), var(3));
+
+    let values = vec![
+        ((per_page * (page - 1)) as u64).into(),
+        (per_page as u64).into(),
+        kind.as_str().into(),
+    ];
+
+    let sort = |sort: Sort| match sort {
+        Sort::Desc => "DESC",
+        Sort::Asc => "ASC",
+    };
+
+    // 같은 값끼리는 이름순
+    let order_by = match sort_by {
+        BookTagSortBy::BookCount(x) => format!(r#""book_count" {}, "name" ASC"#, sort(x)),
+        BookTagSortBy::Name(x) => format!(r#""name" {}"#, sort(x)),
+        BookTagSortBy::LatestCreatedAt(x) => {
+            format!(r#""latest_created_at" {}, "name" ASC"#, sort(x))
+        }
+    };
+
+    let query = format!(
+        r#"
+        SELECT
+            "{book_tags}"."id" AS "id",
+            "{book_tags}"."kind" AS "kind",
+            "{book_tags}"."name" AS "name",
+            CAST(SUM("{tag_stats}"."book_count") AS BIGINT) AS "book_count",
+            MAX("{tag_stats}"."latest_created_at") AS "latest_created_at"
+        FROM
+            "{book_tags}"
+        INNER JOIN "{tag_stats}"
+            ON "{tag_stats}"."book_tag_id" = "{book_tags}"."id"
+        WHERE
+            "{book_tags}"."kind" = {kind_var}
+        GROUP BY
+            "{book_tags}"."id", "{book_tags}"."kind", "{book_tags}"."name"
+        HAVING
+            SUM("{tag_stats}"."book_count") > 0
+        ORDER BY
+            {order_by}
+        LIMIT {limit}
+        OFFSET {offset}
+        "#
+    );
+
+    (query, values)
+}
+
+pub fn where_sql(conditions: &[String]) -> String {
+    if conditions.is_empty() {
+        String::new()
+    } else {
+        format!("WHERE {}", conditions.join(" AND "))
+    }
+}
+
+/// `"books"`에 대한 조건들을 반환함
+///
+/// 바인딩할 값들은 `values` 뒤에 추가되고, 조건의 placeholder는 그 순서를 따름
+pub fn filter_sql(backend: DbBackend, filter: &BookFilter, values: &mut Vec<Value>) -> Vec<String> {
+    let books = book::Entity.as_str();
+    let book_tags = book_tag::Entity.as_str();
+    let books_tag_ref = book::tag_ref::Entity.as_str();
+
+    let mut bind = |value: Value| {
+        values.push(value);
+        placeholder(backend, values.len())
+    };
+
+    let mut conditions = Vec::new();
+
+    if !filter.kinds.is_empty() {
+        let vars = filter
+            .kinds
+            .iter()
+            .map(|kind| bind(kind.as_str().into()))
+            .join(",");
+
+        conditions.push(format!(r#""{books}"."kind" IN ({vars})"#));
+    }
+
+    if !filter.languages.is_empty() {
+        let vars = filter
+            .languages
+            .iter()
+            .map(|language| bind(language.as_str().into()))
+            .join(",");
+
+        conditions.push(format!(r#""{books}"."language" IN ({vars})"#));
+    }
+
+    if let Some(min_page) = filter.min_page {
+        let var = bind((min_page as i32).into());
+        conditions.push(format!(r#""{books}"."page" >= {var}"#));
+    }
+
+    if let Some(max_page) = filter.max_page {
+        let var = bind((max_page as i32).into());
+        conditions.push(format!(r#""{books}"."page" <= {var}"#));
+    }
+
+    if let Some(created_after) = filter.created_after {
+        let var = bind(created_after.into());
+        conditions.push(format!(r#""{books}"."created_at" > {var}"#));
+    }
+
+    if let Some(created_before) = filter.created_before {
+        let var = bind(created_before.into());
+        conditions.push(format!(r#""{books}"."created_at" < {var}"#));
+    }
+
+    if let Some(after_id) = filter.after_id {
+        let var = bind((after_id as i64).into());
+        conditions.push(format!(r#""{books}"."id" > {var}"#));
+    }
+
+    match backend {
+        // 바깥 쿼리에서 같은 테이블을 join할 수 있기 때문에 별칭을 사용함
+        DbBackend::Sqlite => {
+            let mut has_tag = |tag: &BookTag| {
+                let kind = bind(tag.kind().into());
+                let name = bind(tag.name().into());
+
+                format!(
+                    r#"
+                    EXISTS (
+                        SELECT 1 FROM "{books_tag_ref}" AS "filter_ref"
+                        INNER JOIN "{book_tags}" AS "filter_tag"
+                            ON "filter_tag"."id" = "filter_ref"."book_tag_id"
+                        WHERE "filter_ref"."book_id" = "{books}"."id"
+                            AND "filter_tag"."kind" = {kind}
+                            AND "filter_tag"."name" = {name}
+                    )
+                    "#
+                )
+            };
+
+            for tag in &filter.tags {
+                conditions.push(has_tag(tag));
+            }
+
+            for tag in &filter.exclude_tags {
+                conditions.push(format!("NOT {}", has_tag(tag)));
+            }
+        }
+        // `"tag_ids"`의 GIN 인덱스를 사용함
+        //
+        // 없는 태그의 id는 NULL이 되는데, NULL은 어떤 id와도 같지 않아서
+        // 포함할 태그라면 아무 작품도 맞지 않고, 제외할 태그라면 무시됨
+        _ => {
+            let tag_ids = book::TAG_IDS;
+
+            let mut tag_id_array = |tags: &[BookTag]| {
+                let ids = tags
+                    .iter()
+                    .map(|tag| {
+                        let kind = bind(tag.kind().into());
+                        let name = bind(tag.name().into());
+
+                        format!(
+                            r#"
+                            (
+                                SELECT "filter_tag"."id" FROM "{book_tags}" AS "filter_tag"
+                                WHERE "filter_tag"."kind" = {kind}
+                                    AND "filter_tag"."name" = {name}
+                            )
+                            "#
+                        )
+                    })
+                    .join(",");
+
+                format!("ARRAY[{ids}]::BIGINT[]")
+            };
+
+            if !filter.tags.is_empty() {
+                let ids = tag_id_array(&filter.tags);
+                conditions.push(format!(r#""{books}"."{tag_ids}" @> {ids}"#));
+            }
+
+            if !filter.exclude_tags.is_empty() {
+                let ids = tag_id_array(&filter.exclude_tags);
+                conditions.push(format!(r#"NOT ("{books}"."{tag_ids}" && {ids})"#));
+            }
+        }
+    }
+
+    conditions
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use migration::{Migrator, MigratorTrait};
+    use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
+    use sea_orm::{ConnectOptions, Database, DatabaseConnection, DbErr, TransactionTrait};
+    use uuid::Uuid;
+
+    use super::*;
+    use crate::{
+        entity::BookKind,
+        repository::fixture::{book_of, seeded_rng, shuffled_books},
+    };
+
+    /// 1..=n 작품을 섞은 순서대로 추가함, 섞을 때 사용한 seed를 같이 반환함
+    async fn setup(n: u32) -> (DatabaseConnection, u64) {
+        let mut option = ConnectOptions::new("sqlite::memory:".to_string());
+        // 연결마다 다른 데이터베이스가 만들어짐
+        option.max_connections(1);
+
+        let db = Database::connect(option).await.unwrap();
+        let seed = insert_books(&db, n).await;
+
+        (db, seed)
+    }
+
+    /// postgresql에서만 실행되는 쿼리를 확인할 때 사용함, `POSTGRES_TEST_URL`이 없으면 None
+    ///
+    /// 테스트마다 새 schema를 만들어서 `setup`과 같이 작품을 추가함, 끝나면 `teardown_postgresql`로 지움
+    async fn setup_postgresql(n: u32) -> Option<(DatabaseConnection, u64)> {
+        let url = std::env::var("POSTGRES_TEST_URL").ok()?;
+
+        let mut option = ConnectOptions::new(url);
+        // search_path는 연결마다 다름
+        option.max_connections(1);
+
+        let db = Database::connect(option).await.unwrap();
+        let schema = format!("library_test_{}", Uuid::new_v4().to_simple());
+
+        for query in [
+            format!(r#"CREATE SCHEMA "{schema}""#),
+            format!(r#"SET search_path TO "{schema}""#),
+        ] {
+            db.execute(Statement::from_string(DbBackend::Postgres, query))
+                .await
+                .unwrap();
+        }
+
+        let seed = insert_books(&db, n).await;
+
+        Some((db, seed))
+    }
+
+    async fn teardown_postgresql(db: DatabaseConnection) {
+        let query = r#"
+            DO $$
+            BEGIN
+                EXECUTE format('DROP SCHEMA %I CASCADE', current_schema());
+            END
+            $$
+        "#;
+
+        db.execute(Statement::from_string(
+            DbBackend::Postgres,
+            query.to_string(),
+        ))
+        .await
+        .unwrap();
+    }
+
+    async fn count_sql(db: &DatabaseConnection, query: &str) -> i64 {
+        db.query_one(Statement::from_string(
+            db.get_database_backend(),
+            query.to_string(),
+        ))
+        .await
+        .unwrap()
+        .unwrap()
+        .try_get::<i64>("", "count")
+        .unwrap()
+    }
+
+    async fn insert_books(db: &DatabaseConnection, n: u32) -> u64 {
+        Migrator::up(db, None).await.unwrap();
+
+        let (seed, mut rng) = seeded_rng();
+
+        for book in shuffled_books(n, &mut rng) {
+            db.transaction::<_, (), DbErr>(|txn| Box::pin(insert_book(txn, book)))
+                .await
+                .unwrap();
+        }
+
+        seed
+    }
+
+    fn assert_tags(books: &[Book], seed: u64) {
+        for book in books {
+            let expected = book_of(book.id).tags.into_iter().collect::<HashSet<_>>();
+            let actual = book.tags.iter().cloned().collect::<HashSet<_>>();
+
+            assert_eq!(
+                book.tags.len(),
+                expected.len(),
+                "book {}, seed {seed}",
+                book.id
+            );
+            assert_eq!(actual, expected, "book {}, seed {seed}", book.id);
+        }
+    }
+
+    async fn pages(db: &DatabaseConnection, per_page: usize, sort_by: BookSortBy) -> Vec<Book> {
+        let mut books = Vec::new();
+
+        for page in 1.. {
+            let xs = get_many(db, BookFilter::default(), per_page, page, sort_by, true)
+                .await
+                .unwrap();
+
+            if xs.is_empty() {
+                break;
+            }
+
+            books.extend(xs);
+        }
+
+        books
+    }
+
+    #[tokio::test]
+    async fn get_many_sorts_and_groups_shuffled_books() {
+        let (db, seed) = setup(30).await;
+
+        let desc = pages(&db, 7, BookSortBy::Id(Sort::Desc)).await;
+        let asc = pages(&db, 7, BookSortBy::Id(Sort::Asc)).await;
+
+        assert_eq!(
+            desc.iter().map(|x| x.id).collect::<Vec<_>>(),
+            (1..=30).rev().collect::<Vec<_>>(),
+            "seed {seed}"
+        );
+        assert_eq!(
+            asc.iter().map(|x| x.id).collect::<Vec<_>>(),
+            (1..=30).collect::<Vec<_>>(),
+            "seed {seed}"
+        );
+
+        assert_tags(&desc, seed);
+        assert_tags(&asc, seed);
+    }
+
+    #[tokio::test]
+    async fn get_many_random_pages_follow_one_order() {
+        let (db, seed) = setup(30).await;
+        let sort_by = BookSortBy::Random(42);
+
+        let paged = pages(&db, 7, sort_by).await;
+        let whole = get_many(&db, BookFilter::default(), 30, 1, sort_by, true)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            paged.iter().map(|x| x.id).collect::<Vec<_>>(),
+            whole.iter().map(|x| x.id).collect::<Vec<_>>(),
+            "seed {seed}"
+        );
+        assert_eq!(
+            whole.iter().map(|x| x.id).collect::<HashSet<_>>(),
+            (1..=30).collect::<HashSet<_>>(),
+            "seed {seed}"
+        );
+
+        assert_tags(&paged, seed);
+    }
+
+    #[tokio::test]
+    async fn get_many_by_ids_keeps_requested_order() {
+        let (db, seed) = setup(30).await;
+
+        let mut book_ids = (1..=30).collect::<Vec<_>>();
+        book_ids.shuffle(&mut StdRng::seed_from_u64(seed));
+
+        let books = get_many_by_ids(&db, book_ids.clone(), true).await.unwrap();
+
+        assert_eq!(
+            books.iter().map(|x| x.id).collect::<Vec<_>>(),
+            book_ids,
+            "seed {seed}"
+        );
+
+        assert_tags(&books, seed);
+    }
+
+    #[tokio::test]
+    async fn get_many_by_ids_unordered_returns_requested_books() {
+        let (db, seed) = setup(30).await;
+
+        let book_ids = (1..=30).filter(|id| id % 3 == 0).collect::<Vec<_>>();
+
+        let books = get_many_by_ids_unordered(&db, book_ids.clone(), true)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            books.iter().map(|x| x.id).collect::<HashSet<_>>(),
+            book_ids.into_iter().collect::<HashSet<_>>(),
+            "seed {seed}"
+        );
+
+        assert_tags(&books, seed);
+    }
+
+    #[test]
+    fn get_many_by_ids_unordered_skips_order_by() {
+        for backend in [DbBackend::Postgres, DbBackend::Sqlite] {
+            let (ordered, _) = select_books_sql(backend, SelectBy::Ids(vec![3, 1, 2]), false);
+            let (unordered, _) =
+                select_books_sql(backend, SelectBy::IdsUnordered(vec![3, 1, 2]), false);
+
+            assert!(ordered.contains("ORDER BY"), "{ordered}");
+            assert!(!unordered.contains("ORDER BY"), "{unordered}");
+        }
+    }
+
+    #[test]
+    fn postgresql_aggregates_tags_with_json_agg() {
+        let (query, _) = select_books_sql(DbBackend::Postgres, SelectBy::Id(1), true);
+
+        assert!(
+            query.contains(r#"COALESCE(json_agg("tag" ORDER BY "id")::TEXT, '[]')"#),
+            "{query}"
+        );
+        assert!(query.contains("json_build_array"), "{query}");
+        assert!(!query.contains("json_group_array"), "{query}");
+    }
+
+    #[tokio::test]
+    async fn postgresql_reads_tags_of_each_book() {
+        let (db, seed) = match setup_postgresql(30).await {
+            Some(x) => x,
+            None => return,
+        };
+
+        let untagged = Book {
+            tags: Vec::new(),
+            ..book_of(31)
+        };
+
+        db.transaction::<_, (), DbErr>(|txn| Box::pin(insert_book(txn, untagged)))
+            .await
+            .unwrap();
+
+        let books = get_many_by_ids(&db, (1..=31).collect(), true).await;
+        let without_tags = get_many_by_ids(&db, (1..=30).collect(), false).await;
+
+        teardown_postgresql(db).await;
+
+        let (mut books, without_tags) = (books.unwrap(), without_tags.unwrap());
+
+        assert_eq!(
+            books.iter().map(|x| x.id).collect::<Vec<_>>(),
+            (1..=31).collect::<Vec<_>>(),
+            "seed {seed}"
+        );
+
+        // 태그가 없으면 json_agg는 NULL이라서 `[]`로 바꿈
+        let untagged = books.pop().unwrap();
+        assert!(untagged.tags.is_empty(), "seed {seed}");
+
+        assert_tags(&books, seed);
+
+        assert!(
+            without_tags.iter().all(|x| x.tags.is_empty()),
+            "seed {seed}"
+        );
+    }
+
+    #[test]
+    fn postgresql_filters_tags_with_tag_ids() {
+        let filter = BookFilter {
+            tags: vec![BookTagKind::Misc.tag("tag-2")],
+            exclude_tags: vec![BookTagKind::Artist.tag("artist-0")],
+            ..Default::default()
+        };
+        let mut values = Vec::new();
+
+        let conditions = filter_sql(DbBackend::Postgres, &filter, &mut values).join(" AND ");
+
+        assert!(
+            conditions.contains(r#""books"."tag_ids" @> ARRAY["#),
+            "{conditions}"
+        );
+        assert!(
+            conditions.contains(r#"NOT ("books"."tag_ids" && ARRAY["#),
+            "{conditions}"
+        );
+        assert!(!conditions.contains("books_tag_ref"), "{conditions}");
+        assert_eq!(values.len(), 4);
+    }
+
+    #[tokio::test]
+    async fn postgresql_filters_by_tag_ids() {
+        let (db, seed) = match setup_postgresql(30).await {
+            Some(x) => x,
+            None => return,
+        };
+
+        let filter = BookFilter {
+            tags: vec![BookTagKind::Misc.tag("tag-2")],
+            // 없는 태그는 제외할 때 무시됨
+            exclude_tags: vec![
+                BookTagKind::Artist.tag("artist-0"),
+                BookTagKind::Misc.tag("unknown"),
+            ],
+            ..Default::default()
+        };
+        let unknown = BookFilter {
+            tags: vec![BookTagKind::Misc.tag("unknown")],
+            ..Default::default()
+        };
+
+        let books = get_many(&db, filter.clone(), 30, 1, BookSortBy::Id(Sort::Asc), true).await;
+        let book_count = count(&db, filter).await;
+        let unknown_count = count(&db, unknown).await;
+
+        teardown_postgresql(db).await;
+
+        let expected = (1..=30)
+            .filter(|id| id % 5 >= 2 && id % 3 != 0)
+            .collect::<Vec<_>>();
+        let books = books.unwrap();
+
+        assert_eq!(
+            books.iter().map(|x| x.id).collect::<Vec<_>>(),
+            expected,
+            "seed {seed}"
+        );
+        assert_eq!(book_count.unwrap(), expected.len(), "seed {seed}");
+        assert_eq!(unknown_count.unwrap(), 0, "seed {seed}");
+
+        assert_tags(&books, seed);
+    }
+
+    /// `"books_tag_ref"`를 바꾸면 trigger가 `"tag_ids"`를 맞춤
+    #[tokio::test]
+    async fn postgresql_keeps_tag_ids_in_sync() {
+        let (db, seed) = match setup_postgresql(10).await {
+            Some(x) => x,
+            None => return,
+        };
+
+        let out_of_sync = r#"
+            SELECT COUNT(*) AS "count" FROM "books"
+            WHERE "tag_ids" <> ARRAY(
+                SELECT "book_tag_id" FROM "books_tag_ref"
+                WHERE "books_tag_ref"."book_id" = "books"."id"
+                ORDER BY "book_tag_id"
+            )
+        "#;
+        let untagged = r#"SELECT COUNT(*) AS "count" FROM "books" WHERE "tag_ids" = '{}'"#;
+
+        let mut counts = vec![
+            count_sql(&db, out_of_sync).await,
+            count_sql(&db, untagged).await,
+        ];
+
+        for query in [
+            r#"
+            UPDATE "books_tag_ref"
+            SET "book_tag_id" = (
+                SELECT "id" FROM "book_tags" WHERE "kind" = 'misc' AND "name" = 'tag-4'
+            )
+            WHERE "book_id" = 1 AND "book_tag_id" = (
+                SELECT "id" FROM "book_tags" WHERE "kind" = 'artist' AND "name" = 'artist-1'
+            )
+            "#,
+            r#"DELETE FROM "books_tag_ref" WHERE "book_id" = 2"#,
+        ] {
+            db.execute(Statement::from_string(
+                DbBackend::Postgres,
+                query.to_string(),
+            ))
+            .await
+            .unwrap();
+        }
+
+        counts.push(count_sql(&db, out_of_sync).await);
+        counts.push(count_sql(&db, untagged).await);
+
+        teardown_postgresql(db).await;
+
+        assert_eq!(counts, [0, 0, 0, 1], "seed {seed}");
+    }
+
+    #[test]
+    fn postgresql_compares_tag_stats_with_full_outer_join() {
+        let query = tag_stat_drifts_sql(DbBackend::Postgres);
+
+        assert!(query.contains("FULL OUTER JOIN"), "{query}");
+        assert!(
+            !tag_stat_drifts_sql(DbBackend::Sqlite).contains("FULL OUTER JOIN"),
+            "{query}"
+        );
+    }
+
+    #[tokio::test]
+    async fn recompute_tag_stats_reports_and_fixes_drifts() {
+        let (db, seed) = setup(10).await;
+
+        let recompute = || async {
+            db.transaction::<_, _, DbErr>(|txn| Box::pin(recompute_tag_stats(txn)))
+                .await
+                .unwrap()
+        };
+
+        assert!(recompute().await.is_empty(), "seed {seed}");
+
+        // artist-1 = 1, 4, 7, 10번 작품, artist-2 = 2, 5, 8번 작품
+        for query in [
+            r#"
+            UPDATE "tag_stats" SET "book_count" = "book_count" + 2
+            WHERE "book_tag_id" = (SELECT "id" FROM "book_tags" WHERE "name" = 'artist-1')
+            "#,
+            r#"
+            DELETE FROM "tag_stats"
+            WHERE "book_tag_id" = (SELECT "id" FROM "book_tags" WHERE "name" = 'artist-2')
+            "#,
+            r#"
+            INSERT INTO "tag_stats"
+                ("book_tag_id", "book_kind", "language", "book_count", "latest_created_at")
+            SELECT "id", 'doujinshi', 'korean', 3, '2022-07-20T00:00:00Z' FROM "book_tags"
+            WHERE "name" = 'artist-0'
+            "#,
+        ] {
+            db.execute(Statement::from_string(DbBackend::Sqlite, query.to_string()))
+                .await
+                .unwrap();
+        }
+
+        let drifts = recompute()
+            .await
+            .into_iter()
+            .map(|x| (x.tag.name().to_string(), x.book_kind, x.stored, x.actual))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            drifts.iter().collect::<HashSet<_>>(),
+            [
+                ("artist-0".to_string(), BookKind::Doujinshi, 3, 0),
+                ("artist-1".to_string(), BookKind::Manga, 6, 4),
+                ("artist-2".to_string(), BookKind::Manga, 0, 3),
+            ]
+            .iter()
+            .collect::<HashSet<_>>(),
+            "seed {seed}"
+        );
+
+        assert!(recompute().await.is_empty(), "seed {seed}");
+    }
+
+    #[tokio::test]
+    async fn get_many_by_ids_without_ids_returns_nothing() {
+        let (db, seed) = setup(3).await;
+
+        let books = get_many_by_ids(&db, Vec::new(), true).await.unwrap();
+
+        assert!(books.is_empty(), "seed {seed}");
+    }
+
+    #[tokio::test]
+    async fn get_many_by_tag_returns_latest_first() {
+        let (db, seed) = setup(30).await;
+
+        let books = get_many_by_tag(&db, BookTagKind::Misc.tag("tag-4"))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            books.iter().map(|x| x.id).collect::<Vec<_>>(),
+            (1..=30).rev().filter(|id| id % 5 == 4).collect::<Vec<_>>(),
+            "seed {seed}"
+        );
+
+        assert_tags(&books, seed);
+    }
+
+    #[tokio::test]
+    async fn get_many_by_tag_is_limited() {
+        let n = BOOKS_BY_TAG_LIMIT as u32 * 3 + 3;
+        let (db, seed) = setup(n).await;
+
+        let books = get_many_by_tag(&db, BookTagKind::Artist.tag("artist-0"))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            books.iter().map(|x| x.id).collect::<Vec<_>>(),
+            (1..=n)
+                .rev()
+                .filter(|id| id % 3 == 0)
+                .take(BOOKS_BY_TAG_LIMIT)
+                .collect::<Vec<_>>(),
+            "seed {seed}"
+        );
+    }
+}
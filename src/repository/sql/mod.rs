@@ -0,0 +1,20 @@
+//! postgresql과 sqlite에서 같이 사용하는 쿼리
+//!
+//! 쿼리는 두 데이터베이스에서 모두 동작하는 문법으로만 작성하고, placeholder만 `DbBackend`에 따라 다르게 만듦
+
+mod book;
+pub mod saved_search;
+
+pub use book::*;
+
+use sea_orm::DbBackend;
+
+/// n번째(1부터 시작) 바인딩할 값의 placeholder
+///
+/// postgresql은 `$1`, sqlite는 `?1`
+pub fn placeholder(backend: DbBackend, n: usize) -> String {
+    match backend {
+        DbBackend::Sqlite => format!("?{n}"),
+        _ => format!("${n}"),
+    }
+}
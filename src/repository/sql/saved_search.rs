@@ -0,0 +1,183 @@
+//! sqlite도 postgresql과 같은 entity를 사용함
+
+use sea_orm::{
+    sea_query::Expr, ColumnTrait, ConnectionTrait, DbBackend, DbErr, EntityTrait, IdenStatic,
+    QueryFilter, QueryOrder, Statement,
+};
+use uuid::Uuid;
+
+use crate::{database::postgresql::entity::saved_search, entity::SavedSearch};
+
+use super::placeholder;
+
+pub async fn get_one(
+    db: &impl ConnectionTrait,
+    user_id: Uuid,
+    saved_search_id: u64,
+) -> crate::Result<Option<SavedSearch>> {
+    let saved_search = saved_search::Entity::find()
+        .filter(saved_search::Column::Id.eq(saved_search_id as i64))
+        .filter(saved_search::Column::UserId.eq(user_id))
+        .one(db)
+        .await?;
+
+    Ok(saved_search.map(Into::into))
+}
+
+pub async fn get_many(db: &impl ConnectionTrait, user_id: Uuid) -> crate::Result<Vec<SavedSearch>> {
+    let saved_searches = saved_search::Entity::find()
+        .filter(saved_search::Column::UserId.eq(user_id))
+        .order_by_asc(saved_search::Column::Id)
+        .all(db)
+        .await?;
+
+    Ok(saved_searches.into_iter().map(Into::into).collect())
+}
+
+/// 사용자의 저장된 검색이 이미 `max`개 이상이면 추가하지 않고 None
+///
+/// 세는 것과 추가하는 것을 한 문장에서 함, postgresql에서는 동시에 추가하는 요청을 호출하는 쪽에서 막아야 함
+pub async fn add(
+    db: &impl ConnectionTrait,
+    SavedSearch {
+        user_id,
+        name,
+        filter,
+        last_seen_max_id,
+        created_at,
+        updated_at,
+        ..
+    }: SavedSearch,
+    max: usize,
+) -> Result<Option<SavedSearch>, DbErr> {
+    let backend = db.get_database_backend();
+    let saved_searches = saved_search::Entity.as_str();
+    let var = |n| placeholder(backend, n);
+
+    let query = format!(
+        r#"
+        INSERT INTO "{saved_searches}"
+            ("user_id", "name", "filter", "last_seen_max_id", "created_at", "updated_at")
+        SELECT
+            {}, {}, {}, {}, {}, {}
+        WHERE
+            (SELECT COUNT(*) FROM "{saved_searches}" WHERE "user_id" = {}) < {}
+        RETURNING *
+        "#,
+        var(1),
+        var(2),
+        var(3),
+        var(4),
+        var(5),
+        var(6),
+        var(1),
+        var(7),
+    );
+
+    let stmt = Statement::from_sql_and_values(
+        backend,
+        &query,
+        [
+            user_id.into(),
+            name.into(),
+            filter.into(),
+            (last_seen_max_id as i64).into(),
+            created_at.into(),
+            updated_at.into(),
+            (max as i64).into(),
+        ],
+    );
+
+    let model = saved_search::Entity::find()
+        .from_raw_sql(stmt)
+        .one(db)
+        .await?;
+
+    Ok(model.map(Into::into))
+}
+
+pub async fn update(
+    db: &impl ConnectionTrait,
+    SavedSearch {
+        id,
+        user_id,
+        name,
+        filter,
+        last_seen_max_id,
+        updated_at,
+        ..
+    }: SavedSearch,
+) -> crate::Result<bool> {
+    use saved_search::Column;
+
+    let r = saved_search::Entity::update_many()
+        .col_expr(Column::Name, Expr::value(name))
+        .col_expr(Column::Filter, Expr::value(filter))
+        .col_expr(Column::LastSeenMaxId, Expr::value(last_seen_max_id as i64))
+        .col_expr(Column::UpdatedAt, Expr::value(updated_at))
+        .filter(Column::Id.eq(id as i64))
+        .filter(Column::UserId.eq(user_id))
+        .exec(db)
+        .await?;
+
+    Ok(r.rows_affected > 0)
+}
+
+/// 동시에 실행된 `update`를 덮어쓰지 않도록 `last_seen_max_id`만 바꿈
+pub async fn mark_seen(
+    db: &impl ConnectionTrait,
+    user_id: Uuid,
+    saved_search_id: u64,
+    max_id: u32,
+) -> crate::Result<bool> {
+    let backend = db.get_database_backend();
+    let saved_searches = saved_search::Entity.as_str();
+    let var = |n| placeholder(backend, n);
+
+    // sqlite는 GREATEST 대신 인자가 여러개인 MAX를 사용함
+    let greatest = match backend {
+        DbBackend::Sqlite => "MAX",
+        _ => "GREATEST",
+    };
+
+    let query = format!(
+        r#"
+        UPDATE "{saved_searches}"
+        SET "last_seen_max_id" = {greatest}("last_seen_max_id", {})
+        WHERE "id" = {} AND "user_id" = {}
+        "#,
+        var(1),
+        var(2),
+        var(3),
+    );
+
+    let stmt = Statement::from_sql_and_values(
+        backend,
+        &query,
+        [
+            (max_id as i64).into(),
+            (saved_search_id as i64).into(),
+            user_id.into(),
+        ],
+    );
+
+    let r = db.execute(stmt).await?;
+
+    Ok(r.rows_affected() > 0)
+}
+
+pub async fn remove(
+    db: &impl ConnectionTrait,
+    user_id: Uuid,
+    saved_search_id: u64,
+) -> crate::Result<bool> {
+    use saved_search::Column;
+
+    let r = saved_search::Entity::delete_many()
+        .filter(Column::Id.eq(saved_search_id as i64))
+        .filter(Column::UserId.eq(user_id))
+        .exec(db)
+        .await?;
+
+    Ok(r.rows_affected > 0)
+}
@@ -0,0 +1,123 @@
+use sai::{Component, Injected};
+use sea_orm::{DbErr, TransactionError, TransactionTrait};
+
+use crate::{
+    constant::sqlite,
+    database::DatabaseSet,
+    entity::{
+        Book, BookFacets, BookFilter, BookGroupByTag, BookSortBy, BookTag, BookTagKind,
+        BookTagSortBy, BookTagStatDrift, BookTagSummary,
+    },
+    repository::{r#trait::BookRepository, sql},
+};
+
+#[derive(Component)]
+pub struct SqliteBookRepository {
+    #[injected]
+    database: Injected<DatabaseSet>,
+}
+
+/// 쿼리는 postgresql과 같이 사용함, `repository::sql` 참조
+#[async_trait::async_trait]
+impl BookRepository for SqliteBookRepository {
+    async fn get_one(&self, book_id: u32) -> crate::Result<Option<Book>> {
+        sql::get_one(self.database.sqlite(), book_id).await
+    }
+
+    async fn get_many(
+        &self,
+        filter: BookFilter,
+        per_page: usize,
+        page: usize,
+        sort_by: BookSortBy,
+        include_tags: bool,
+    ) -> crate::Result<Vec<Book>> {
+        sql::get_many(
+            self.database.sqlite(),
+            filter,
+            per_page,
+            page,
+            sort_by,
+            include_tags,
+        )
+        .await
+    }
+
+    async fn count(&self, filter: BookFilter) -> crate::Result<usize> {
+        sql::count(self.database.sqlite(), filter).await
+    }
+
+    /// sqlite에는 통계가 없어서 직접 셈
+    async fn estimate_count(&self) -> crate::Result<usize> {
+        self.count(BookFilter::default()).await
+    }
+
+    async fn get_facets(&self, filter: BookFilter, tag_limit: usize) -> crate::Result<BookFacets> {
+        sql::get_facets(self.database.sqlite(), filter, tag_limit).await
+    }
+
+    async fn get_many_by_ids(
+        &self,
+        book_ids: Vec<u32>,
+        include_tags: bool,
+    ) -> crate::Result<Vec<Book>> {
+        sql::get_many_by_ids(self.database.sqlite(), book_ids, include_tags).await
+    }
+
+    async fn get_many_by_ids_unordered(
+        &self,
+        book_ids: Vec<u32>,
+        include_tags: bool,
+    ) -> crate::Result<Vec<Book>> {
+        sql::get_many_by_ids_unordered(self.database.sqlite(), book_ids, include_tags).await
+    }
+
+    async fn get_tags(
+        &self,
+        kind: BookTagKind,
+        per_page: usize,
+        page: usize,
+        sort_by: BookTagSortBy,
+    ) -> crate::Result<Vec<BookTagSummary>> {
+        sql::get_tags(self.database.sqlite(), kind, per_page, page, sort_by).await
+    }
+
+    async fn get_many_by_tags(
+        &self,
+        book_tags: Vec<BookTag>,
+    ) -> crate::Result<Vec<BookGroupByTag>> {
+        sql::get_many_by_tags(self.database.sqlite(), book_tags).await
+    }
+
+    async fn get_many_by_tag(&self, book_tag: BookTag) -> crate::Result<Vec<Book>> {
+        sql::get_many_by_tag(self.database.sqlite(), book_tag).await
+    }
+
+    async fn add(&self, book: Book) -> crate::Result<bool> {
+        let db = self.database.sqlite();
+
+        let r = db
+            .transaction::<_, (), DbErr>(|txn| Box::pin(sql::insert_book(txn, book)))
+            .await;
+
+        match r {
+            Ok(_) => Ok(true),
+            Err(TransactionError::Connection(err) | TransactionError::Transaction(err))
+                if err.to_string().contains(sqlite::UNIQUE_CONSTRAINT_FAILED) =>
+            {
+                Ok(false)
+            }
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    async fn recompute_tag_stats(&self) -> crate::Result<Vec<BookTagStatDrift>> {
+        let db = self.database.sqlite();
+
+        let drifts = db
+            .transaction::<_, _, DbErr>(|txn| Box::pin(sql::recompute_tag_stats(txn)))
+            .await?;
+
+        Ok(drifts)
+    }
+}
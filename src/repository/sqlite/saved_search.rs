@@ -0,0 +1,59 @@
+use sai::{Component, Injected};
+use uuid::Uuid;
+
+use crate::{
+    database::DatabaseSet,
+    entity::SavedSearch,
+    repository::{r#trait::SavedSearchRepository, sql},
+};
+
+#[derive(Component)]
+pub struct SqliteSavedSearchRepository {
+    #[injected]
+    database: Injected<DatabaseSet>,
+}
+
+/// 쿼리는 postgresql과 같이 사용함, `repository::sql::saved_search` 참조
+#[async_trait::async_trait]
+impl SavedSearchRepository for SqliteSavedSearchRepository {
+    async fn get_one(
+        &self,
+        user_id: Uuid,
+        saved_search_id: u64,
+    ) -> crate::Result<Option<SavedSearch>> {
+        sql::saved_search::get_one(self.database.sqlite(), user_id, saved_search_id).await
+    }
+
+    async fn get_many(&self, user_id: Uuid) -> crate::Result<Vec<SavedSearch>> {
+        sql::saved_search::get_many(self.database.sqlite(), user_id).await
+    }
+
+    /// sqlite는 한 문장을 실행하는 동안 다른 쓰기를 막음
+    async fn add(
+        &self,
+        saved_search: SavedSearch,
+        max: usize,
+    ) -> crate::Result<Option<SavedSearch>> {
+        let saved_search =
+            sql::saved_search::add(self.database.sqlite(), saved_search, max).await?;
+
+        Ok(saved_search)
+    }
+
+    async fn update(&self, saved_search: SavedSearch) -> crate::Result<bool> {
+        sql::saved_search::update(self.database.sqlite(), saved_search).await
+    }
+
+    async fn mark_seen(
+        &self,
+        user_id: Uuid,
+        saved_search_id: u64,
+        max_id: u32,
+    ) -> crate::Result<bool> {
+        sql::saved_search::mark_seen(self.database.sqlite(), user_id, saved_search_id, max_id).await
+    }
+
+    async fn remove(&self, user_id: Uuid, saved_search_id: u64) -> crate::Result<bool> {
+        sql::saved_search::remove(self.database.sqlite(), user_id, saved_search_id).await
+    }
+}
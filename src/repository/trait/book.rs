@@ -1,4 +1,10 @@
-use crate::entity::{Book, BookGroupByTag, BookKind, BookSortBy, BookTag};
+use crate::entity::{
+    Book, BookFacets, BookFilter, BookGroupByTag, BookSortBy, BookTag, BookTagKind, BookTagSortBy,
+    BookTagStatDrift, BookTagSummary,
+};
+
+/// `get_many_by_tag(s)`가 태그마다 가져올 최대 작품 수
+pub const BOOKS_BY_TAG_LIMIT: usize = 100;
 
 #[async_trait::async_trait]
 pub trait BookRepository: Send + Sync {
@@ -6,20 +12,58 @@ pub trait BookRepository: Send + Sync {
 
     async fn get_many(
         &self,
-        kind: Option<BookKind>,
+        filter: BookFilter,
         per_page: usize,
         page: usize,
         sort_by: BookSortBy,
+        include_tags: bool,
+    ) -> crate::Result<Vec<Book>>;
+
+    /// `get_many`와 같은 필터를 사용함
+    async fn count(&self, filter: BookFilter) -> crate::Result<usize>;
+
+    /// 통계로 추정한 전체 작품 수, 필터가 없을 때만 사용함
+    async fn estimate_count(&self) -> crate::Result<usize>;
+
+    /// `tag_limit`은 태그 종류마다 가져올 최대 갯수
+    async fn get_facets(&self, filter: BookFilter, tag_limit: usize) -> crate::Result<BookFacets>;
+
+    /// `include_tags`가 false면 태그를 가져오지 않음
+    async fn get_many_by_ids(
+        &self,
+        book_ids: Vec<u32>,
+        include_tags: bool,
     ) -> crate::Result<Vec<Book>>;
 
-    async fn get_many_by_ids(&self, book_ids: Vec<u32>) -> crate::Result<Vec<Book>>;
+    /// `get_many_by_ids`와 같지만 요청한 순서를 맞추지 않음
+    ///
+    /// 호출하는 쪽에서 다시 정렬할 때 사용함, id가 많아도 정렬하는 비용이 없음
+    async fn get_many_by_ids_unordered(
+        &self,
+        book_ids: Vec<u32>,
+        include_tags: bool,
+    ) -> crate::Result<Vec<Book>>;
 
+    /// `kind` 태그들을 붙은 작품 수, 가장 최근에 추가된 작품의 시각과 같이 가져옴
+    async fn get_tags(
+        &self,
+        kind: BookTagKind,
+        per_page: usize,
+        page: usize,
+        sort_by: BookTagSortBy,
+    ) -> crate::Result<Vec<BookTagSummary>>;
+
+    /// 태그마다 최근 작품부터 최대 `BOOKS_BY_TAG_LIMIT`개
     async fn get_many_by_tags(&self, book_tags: Vec<BookTag>)
         -> crate::Result<Vec<BookGroupByTag>>;
 
+    /// 최근 작품부터 최대 `BOOKS_BY_TAG_LIMIT`개
     async fn get_many_by_tag(&self, book_tag: BookTag) -> crate::Result<Vec<Book>>;
 
     async fn add(&self, book: Book) -> crate::Result<bool>;
 
+    /// 태그 통계를 처음부터 다시 세고, 저장되어 있던 값과 달랐던 항목들을 반환함
+    async fn recompute_tag_stats(&self) -> crate::Result<Vec<BookTagStatDrift>>;
+
     // async fn add_tags(&self, )
 }
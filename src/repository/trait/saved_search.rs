@@ -0,0 +1,43 @@
+use uuid::Uuid;
+
+use crate::entity::SavedSearch;
+
+/// 사용자의 것이 아닌 저장된 검색은 없는 것과 같음
+#[async_trait::async_trait]
+pub trait SavedSearchRepository: Send + Sync {
+    async fn get_one(
+        &self,
+        user_id: Uuid,
+        saved_search_id: u64,
+    ) -> crate::Result<Option<SavedSearch>>;
+
+    /// 만든 순서대로
+    async fn get_many(&self, user_id: Uuid) -> crate::Result<Vec<SavedSearch>>;
+
+    /// id가 채워진 저장된 검색을 반환함
+    ///
+    /// 사용자의 저장된 검색이 이미 `max`개 이상이면 추가하지 않고 None
+    async fn add(
+        &self,
+        saved_search: SavedSearch,
+        max: usize,
+    ) -> crate::Result<Option<SavedSearch>>;
+
+    /// `name`, `filter`, `last_seen_max_id`, `updated_at`을 바꿈
+    ///
+    /// 없으면 false
+    async fn update(&self, saved_search: SavedSearch) -> crate::Result<bool>;
+
+    /// `last_seen_max_id`만 `max_id`까지 올림, 이미 더 크면 그대로 둠
+    ///
+    /// 없으면 false
+    async fn mark_seen(
+        &self,
+        user_id: Uuid,
+        saved_search_id: u64,
+        max_id: u32,
+    ) -> crate::Result<bool>;
+
+    /// 없으면 false
+    async fn remove(&self, user_id: Uuid, saved_search_id: u64) -> crate::Result<bool>;
+}
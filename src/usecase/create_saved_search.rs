@@ -0,0 +1,101 @@
+use std::sync::Arc;
+
+use chrono::Utc;
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::{
+    entity,
+    error::UseCaseError,
+    model, payload,
+    repository::{r#trait::SavedSearchRepository, RepositorySet},
+};
+
+use super::get_saved_search::latest_book_id;
+
+/// 사용자마다 저장할 수 있는 검색의 최대 갯수
+pub const MAX_SAVED_SEARCHES: usize = 50;
+
+/// ```json
+/// {
+///     "name": "korean doujinshi",
+///     "filter": { "kind": ["doujinshi"], "language": ["korean"] }
+/// }
+/// ```
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct Payload {
+    #[serde(skip)]
+    pub user_id: Uuid,
+    pub name: String,
+    #[serde(default)]
+    pub filter: payload::BookFilter,
+}
+
+impl Payload {
+    pub fn check(self) -> crate::Result<Self> {
+        let name = check_name(&self.name)?;
+
+        // 저장하기 전에 실행할 수 있는 필터인지 확인함
+        self.filter.clone().check()?;
+
+        Ok(Self { name, ..self })
+    }
+}
+
+pub fn check_name(name: &str) -> crate::Result<String> {
+    let name = name.trim();
+
+    if name.is_empty() || name.chars().count() > 100 {
+        return Err(payload::Error::InvalidSavedSearchName.into());
+    }
+
+    Ok(name.to_owned())
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("Can't save more than {0} searches")]
+    TooManySavedSearches(usize),
+}
+
+impl From<Error> for crate::Error {
+    fn from(err: Error) -> Self {
+        UseCaseError::from(err).into()
+    }
+}
+
+pub type Model = model::SavedSearch;
+
+pub async fn execute(
+    Payload {
+        user_id,
+        name,
+        filter,
+    }: Payload,
+    repository: Arc<RepositorySet>,
+) -> crate::Result<Model> {
+    // 지금 있는 작품들은 새 작품으로 세지 않음
+    let last_seen_max_id = latest_book_id(filter.clone().check()?, &repository).await?;
+
+    let now = Utc::now();
+
+    let saved_search = repository
+        .saved_search()
+        .add(
+            entity::SavedSearch {
+                id: 0,
+                user_id,
+                name,
+                filter: serde_json::to_string(&filter).expect("json serialize"),
+                last_seen_max_id,
+                created_at: now,
+                updated_at: now,
+            },
+            MAX_SAVED_SEARCHES,
+        )
+        .await?
+        .ok_or(Error::TooManySavedSearches(MAX_SAVED_SEARCHES))?;
+
+    Ok(model::SavedSearch::new(saved_search, 0))
+}
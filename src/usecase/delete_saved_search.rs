@@ -0,0 +1,63 @@
+use std::sync::Arc;
+
+use util::http::url::PathVariable;
+use uuid::Uuid;
+
+use crate::{
+    error::UseCaseError,
+    payload,
+    repository::{r#trait::SavedSearchRepository, RepositorySet},
+};
+
+#[derive(Debug)]
+pub struct Payload {
+    pub user_id: Uuid,
+    pub saved_search_id: u64,
+}
+
+impl TryFrom<(Uuid, PathVariable)> for Payload {
+    type Error = crate::Error;
+
+    fn try_from((user_id, mut path_var): (Uuid, PathVariable)) -> Result<Self, Self::Error> {
+        match path_var.next_variable::<u64>() {
+            Some(saved_search_id) => Ok(Payload {
+                user_id,
+                saved_search_id,
+            }),
+            None => Err(payload::Error::InvalidPathVariable("saved_search_id", "number").into()),
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("Not found saved search")]
+    NotFoundSavedSearch,
+}
+
+impl From<Error> for crate::Error {
+    fn from(err: Error) -> Self {
+        UseCaseError::from(err).into()
+    }
+}
+
+pub type Model = ();
+
+pub async fn execute(
+    Payload {
+        user_id,
+        saved_search_id,
+    }: Payload,
+    repository: Arc<RepositorySet>,
+) -> crate::Result<Model> {
+    let removed = repository
+        .saved_search()
+        .remove(user_id, saved_search_id)
+        .await?;
+
+    if !removed {
+        return Err(Error::NotFoundSavedSearch.into());
+    }
+
+    Ok(())
+}
@@ -0,0 +1,28 @@
+use std::sync::Arc;
+
+use crate::{error::UseCaseError, model, repository::RepositorySet};
+
+/// 내부 요청에서만 실행함
+#[derive(Debug)]
+pub struct Payload {}
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {}
+
+impl From<Error> for crate::Error {
+    fn from(err: Error) -> Self {
+        UseCaseError::from(err).into()
+    }
+}
+
+pub type Model = model::BookCacheStats;
+
+/// 캐시를 사용하지 않으면 모두 0임
+pub async fn execute(_: Payload, repository: Arc<RepositorySet>) -> crate::Result<Model> {
+    let stats = repository
+        .book_cache()
+        .map(|book_cache| book_cache.stats().into())
+        .unwrap_or_default();
+
+    Ok(stats)
+}
@@ -0,0 +1,81 @@
+use std::sync::Arc;
+
+use hyper::{Body, Request};
+use serde::Deserialize;
+use util::validate::ValidatorNumberExt;
+
+use crate::{
+    entity,
+    error::UseCaseError,
+    model, payload,
+    repository::{r#trait::BookRepository, RepositorySet},
+};
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct Payload {
+    /// 태그 종류마다 가져올 최대 갯수
+    pub tag_limit: Option<usize>,
+    /// `get_books::Payload`와 같은 필터
+    #[serde(skip)]
+    pub filter: entity::BookFilter,
+}
+
+impl Payload {
+    pub fn check(self) -> crate::Result<Self> {
+        let tag_limit = self
+            .tag_limit
+            .unwrap_or(10)
+            .validate()
+            .min(1)
+            .max(50)
+            .take()
+            .map_err(payload::Error::InvalidTagLimit)?;
+
+        Ok(Self {
+            tag_limit: Some(tag_limit),
+            filter: self.filter,
+        })
+    }
+}
+
+impl TryFrom<&mut Request<Body>> for Payload {
+    type Error = crate::Error;
+
+    fn try_from(request: &mut Request<Body>) -> Result<Self, Self::Error> {
+        let qs = request.uri().query().unwrap_or_default();
+        let payload: Payload =
+            serde_qs::from_str(qs).map_err(payload::Error::QuerystringDeserialize)?;
+        let filter: payload::BookFilter =
+            serde_qs::from_str(qs).map_err(payload::Error::QuerystringDeserialize)?;
+
+        Payload {
+            filter: filter.check()?,
+            ..payload
+        }
+        .check()
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {}
+
+impl From<Error> for crate::Error {
+    fn from(err: Error) -> Self {
+        UseCaseError::from(err).into()
+    }
+}
+
+pub type Model = model::BookFacets;
+
+pub async fn execute(
+    Payload { tag_limit, filter }: Payload,
+    repository: Arc<RepositorySet>,
+) -> crate::Result<Model> {
+    let facets = repository
+        .book()
+        .get_facets(filter, tag_limit.unwrap())
+        .await?;
+
+    Ok(facets.into())
+}
@@ -0,0 +1,106 @@
+use std::sync::Arc;
+
+use hyper::{Body, Request};
+use serde::Deserialize;
+use util::validate::ValidatorNumberExt;
+
+use crate::{
+    entity,
+    error::UseCaseError,
+    model, payload,
+    repository::{r#trait::BookRepository, RepositorySet},
+};
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct Payload {
+    /// 경로에서 정해짐 e.g. `/artists` -> `artist`
+    #[serde(skip)]
+    pub kind: Option<entity::BookTagKind>,
+    pub per_page: Option<usize>,
+    pub page: Option<usize>,
+    pub sort_by: Option<payload::BookTagSortBy>,
+}
+
+impl Payload {
+    pub fn check(self) -> crate::Result<Self> {
+        let per_page = self
+            .per_page
+            .unwrap_or(25)
+            .validate()
+            .min(1)
+            .max(100)
+            .take()
+            .map_err(payload::Error::InvalidPerPage)?;
+
+        let page = self
+            .page
+            .unwrap_or(1)
+            .validate()
+            .min(1)
+            .take()
+            .map_err(payload::Error::InvalidPage)?;
+
+        let sort_by = self
+            .sort_by
+            .unwrap_or(payload::BookTagSortBy::BookCountDesc);
+
+        Ok(Self {
+            kind: self.kind,
+            per_page: Some(per_page),
+            page: Some(page),
+            sort_by: Some(sort_by),
+        })
+    }
+}
+
+impl TryFrom<(entity::BookTagKind, &mut Request<Body>)> for Payload {
+    type Error = crate::Error;
+
+    fn try_from(
+        (kind, request): (entity::BookTagKind, &mut Request<Body>),
+    ) -> Result<Self, Self::Error> {
+        let qs = request.uri().query().unwrap_or_default();
+        let payload: Payload =
+            serde_qs::from_str(qs).map_err(payload::Error::QuerystringDeserialize)?;
+
+        Payload {
+            kind: Some(kind),
+            ..payload
+        }
+        .check()
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {}
+
+impl From<Error> for crate::Error {
+    fn from(err: Error) -> Self {
+        UseCaseError::from(err).into()
+    }
+}
+
+pub type Model = Vec<model::BookTagSummary>;
+
+pub async fn execute(
+    Payload {
+        kind,
+        per_page,
+        page,
+        sort_by,
+    }: Payload,
+    repository: Arc<RepositorySet>,
+) -> crate::Result<Model> {
+    let tags = repository
+        .book()
+        .get_tags(
+            kind.unwrap(),
+            per_page.unwrap(),
+            page.unwrap(),
+            sort_by.unwrap().into(),
+        )
+        .await?;
+
+    Ok(tags.into_iter().map(model::BookTagSummary::from).collect())
+}
@@ -1,11 +1,11 @@
 use std::sync::Arc;
 
 use hyper::{Body, Request};
-use itertools::Itertools;
 use serde::Deserialize;
-use util::{validate::ValidatorNumberExt, MapInto};
+use util::validate::ValidatorNumberExt;
 
 use crate::{
+    entity,
     error::UseCaseError,
     model, payload,
     repository::{r#trait::BookRepository, RepositorySet},
@@ -14,10 +14,24 @@ use crate::{
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "kebab-case")]
 pub struct Payload {
-    pub kind: Option<payload::BookKind>,
     pub per_page: Option<usize>,
     pub page: Option<usize>,
     pub sort_by: Option<payload::BookSortBy>,
+    /// `sort-by=random`에서 페이지를 넘겨도 같은 순서를 유지하려면 같은 seed를 보내야 함
+    ///
+    /// 보내지 않으면 새로 만들고, 만든 seed는 `X-Random-Seed`로 응답함
+    pub seed: Option<u32>,
+    /// 응답에 전체 갯수와 페이지 정보를 포함함
+    pub with_total: Option<bool>,
+    pub total_mode: Option<payload::TotalMode>,
+    /// `fields=id,title,kind`
+    pub fields: Option<String>,
+    pub include_tags: Option<bool>,
+    #[serde(skip)]
+    pub book_fields: model::BookFields,
+    /// 쿼리스트링에서 `payload::BookFilter`로 따로 읽어서 검사한 값
+    #[serde(skip)]
+    pub filter: entity::BookFilter,
 }
 
 impl Payload {
@@ -41,11 +55,21 @@ impl Payload {
 
         let sort_by = self.sort_by.unwrap_or(payload::BookSortBy::IdDesc);
 
+        let seed = self.seed.unwrap_or_else(payload::random_seed);
+
+        let book_fields = payload::book_fields(self.fields.as_deref(), self.include_tags)?;
+
         Ok(Self {
-            kind: self.kind,
             per_page: Some(per_page),
             page: Some(page),
             sort_by: Some(sort_by),
+            seed: Some(seed),
+            with_total: Some(self.with_total.unwrap_or(false)),
+            total_mode: Some(self.total_mode.unwrap_or(payload::TotalMode::Exact)),
+            fields: self.fields,
+            include_tags: Some(book_fields.tags),
+            book_fields,
+            filter: self.filter,
         })
     }
 }
@@ -57,8 +81,29 @@ impl TryFrom<&mut Request<Body>> for Payload {
         let qs = request.uri().query().unwrap_or_default();
         let payload: Payload =
             serde_qs::from_str(qs).map_err(payload::Error::QuerystringDeserialize)?;
+        let filter: payload::BookFilter =
+            serde_qs::from_str(qs).map_err(payload::Error::QuerystringDeserialize)?;
 
-        payload.check()
+        Payload {
+            filter: filter.check()?,
+            ..payload
+        }
+        .check()
+    }
+}
+
+/// `/artists/:name/books`처럼 태그 하나에 대한 작품 목록
+impl TryFrom<(entity::BookTag, &mut Request<Body>)> for Payload {
+    type Error = crate::Error;
+
+    fn try_from(
+        (book_tag, request): (entity::BookTag, &mut Request<Body>),
+    ) -> Result<Self, Self::Error> {
+        let mut payload = Payload::try_from(request)?;
+
+        payload.filter.tags.push(book_tag);
+
+        Ok(payload)
     }
 }
 
@@ -71,26 +116,59 @@ impl From<Error> for crate::Error {
     }
 }
 
-pub type Model = Vec<model::Book>;
+pub type Model = model::BookPage;
 
 pub async fn execute(
     Payload {
-        kind,
         per_page,
         page,
         sort_by,
+        seed,
+        with_total,
+        total_mode,
+        book_fields,
+        filter,
+        ..
     }: Payload,
     repository: Arc<RepositorySet>,
 ) -> crate::Result<Model> {
-    let books = repository
-        .book()
-        .get_many(
-            kind.map_into(),
-            per_page.unwrap(),
-            page.unwrap(),
-            sort_by.map_into().unwrap(),
-        )
-        .await?;
-
-    Ok(books.into_iter().map_into().collect())
+    let (per_page, page) = (per_page.unwrap(), page.unwrap());
+
+    let book_repository = repository.book();
+
+    let get_books = book_repository.get_many(
+        filter.clone(),
+        per_page,
+        page,
+        sort_by.unwrap().with_seed(seed.unwrap()),
+        book_fields.tags,
+    );
+
+    let count_books = async {
+        if !with_total.unwrap() {
+            return Ok(None);
+        }
+
+        let total = match total_mode.unwrap() {
+            payload::TotalMode::Estimated if filter.is_empty() => {
+                model::Total::Estimated(book_repository.estimate_count().await?)
+            }
+            _ => model::Total::Exact(book_repository.count(filter.clone()).await?),
+        };
+
+        crate::Result::Ok(Some(total))
+    };
+
+    let (books, total) = tokio::try_join!(get_books, count_books)?;
+
+    Ok(model::BookPage {
+        books: books
+            .into_iter()
+            .map(|book| model::Book::from(book).project(book_fields))
+            .collect(),
+        total,
+        page,
+        per_page,
+        seed: matches!(sort_by, Some(payload::BookSortBy::Random)).then(|| seed.unwrap()),
+    })
 }
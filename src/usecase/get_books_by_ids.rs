@@ -1,9 +1,7 @@
 use std::sync::Arc;
 
 use hyper::{Body, Request};
-use itertools::Itertools;
 use serde::Deserialize;
-use util::{validate::ValidatorNumberExt, MapInto};
 
 use crate::{
     error::UseCaseError,
@@ -15,6 +13,11 @@ use crate::{
 #[serde(rename_all = "kebab-case")]
 pub struct Payload {
     pub ids: Vec<u32>,
+    /// `fields=id,title,kind`
+    pub fields: Option<String>,
+    pub include_tags: Option<bool>,
+    #[serde(skip)]
+    pub book_fields: model::BookFields,
 }
 
 impl Payload {
@@ -25,7 +28,13 @@ impl Payload {
             );
         }
 
-        Ok(self)
+        let book_fields = payload::book_fields(self.fields.as_deref(), self.include_tags)?;
+
+        Ok(Self {
+            include_tags: Some(book_fields.tags),
+            book_fields,
+            ..self
+        })
     }
 }
 
@@ -53,10 +62,18 @@ impl From<Error> for crate::Error {
 pub type Model = Vec<model::Book>;
 
 pub async fn execute(
-    Payload { ids }: Payload,
+    Payload {
+        ids, book_fields, ..
+    }: Payload,
     repository: Arc<RepositorySet>,
 ) -> crate::Result<Model> {
-    let books = repository.book().get_many_by_ids(ids).await?;
+    let books = repository
+        .book()
+        .get_many_by_ids(ids, book_fields.tags)
+        .await?;
 
-    Ok(books.into_iter().map_into().collect())
+    Ok(books
+        .into_iter()
+        .map(|book| model::Book::from(book).project(book_fields))
+        .collect())
 }
@@ -0,0 +1,60 @@
+use std::sync::Arc;
+
+use hyper::{Body, Request};
+
+use crate::{
+    entity::{self, BookSortBy},
+    error::UseCaseError,
+    model, payload,
+    repository::{r#trait::BookRepository, RepositorySet},
+};
+
+#[derive(Debug)]
+pub struct Payload {
+    pub filter: entity::BookFilter,
+}
+
+impl TryFrom<&mut Request<Body>> for Payload {
+    type Error = crate::Error;
+
+    fn try_from(request: &mut Request<Body>) -> Result<Self, Self::Error> {
+        let qs = request.uri().query().unwrap_or_default();
+        let filter: payload::BookFilter =
+            serde_qs::from_str(qs).map_err(payload::Error::QuerystringDeserialize)?;
+
+        Ok(Self {
+            filter: filter.check()?,
+        })
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("Not found book")]
+    NotFoundBook,
+}
+
+impl From<Error> for crate::Error {
+    fn from(err: Error) -> Self {
+        UseCaseError::from(err).into()
+    }
+}
+
+pub type Model = model::Book;
+
+pub async fn execute(
+    Payload { filter }: Payload,
+    repository: Arc<RepositorySet>,
+) -> crate::Result<Model> {
+    let seed = payload::random_seed();
+
+    let book = repository
+        .book()
+        .get_many(filter, 1, 1, BookSortBy::Random(seed), true)
+        .await?
+        .into_iter()
+        .next()
+        .ok_or(Error::NotFoundBook)?;
+
+    Ok(book.into())
+}
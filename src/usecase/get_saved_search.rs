@@ -0,0 +1,106 @@
+use std::sync::Arc;
+
+use util::http::url::PathVariable;
+use uuid::Uuid;
+
+use crate::{
+    entity::{self, BookSortBy, Sort},
+    error::UseCaseError,
+    model, payload,
+    repository::{
+        r#trait::{BookRepository, SavedSearchRepository},
+        RepositorySet,
+    },
+};
+
+#[derive(Debug)]
+pub struct Payload {
+    pub user_id: Uuid,
+    pub saved_search_id: u64,
+}
+
+impl TryFrom<(Uuid, PathVariable)> for Payload {
+    type Error = crate::Error;
+
+    fn try_from((user_id, mut path_var): (Uuid, PathVariable)) -> Result<Self, Self::Error> {
+        match path_var.next_variable::<u64>() {
+            Some(saved_search_id) => Ok(Payload {
+                user_id,
+                saved_search_id,
+            }),
+            None => Err(payload::Error::InvalidPathVariable("saved_search_id", "number").into()),
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("Not found saved search")]
+    NotFoundSavedSearch,
+    /// 저장할 때 검사한 필터라서 읽지 못하면 서버의 문제임
+    #[error("Can't read the filter of saved search {0}: {1}")]
+    InvalidSavedFilter(u64, #[source] serde_json::Error),
+}
+
+impl From<Error> for crate::Error {
+    fn from(err: Error) -> Self {
+        UseCaseError::from(err).into()
+    }
+}
+
+pub type Model = model::SavedSearch;
+
+pub async fn execute(
+    Payload {
+        user_id,
+        saved_search_id,
+    }: Payload,
+    repository: Arc<RepositorySet>,
+) -> crate::Result<Model> {
+    let saved_search = repository
+        .saved_search()
+        .get_one(user_id, saved_search_id)
+        .await?
+        .ok_or(Error::NotFoundSavedSearch)?;
+
+    with_new_count(saved_search, repository).await
+}
+
+/// 저장된 필터는 실행할 때마다 다시 검사함
+pub fn filter_of(saved_search: &entity::SavedSearch) -> crate::Result<entity::BookFilter> {
+    let filter: payload::BookFilter = serde_json::from_str(&saved_search.filter)
+        .map_err(|err| Error::InvalidSavedFilter(saved_search.id, err))?;
+
+    Ok(filter.check()?)
+}
+
+/// 필터에 맞는 작품 중에서 가장 최근 작품의 id, 없으면 0
+pub async fn latest_book_id(
+    filter: entity::BookFilter,
+    repository: &RepositorySet,
+) -> crate::Result<u32> {
+    let latest = repository
+        .book()
+        .get_many(filter, 1, 1, BookSortBy::Id(Sort::Desc), false)
+        .await?
+        .into_iter()
+        .next()
+        .map(|book| book.id)
+        .unwrap_or(0);
+
+    Ok(latest)
+}
+
+pub async fn with_new_count(
+    saved_search: entity::SavedSearch,
+    repository: Arc<RepositorySet>,
+) -> crate::Result<model::SavedSearch> {
+    let filter = entity::BookFilter {
+        after_id: Some(saved_search.last_seen_max_id),
+        ..filter_of(&saved_search)?
+    };
+
+    let new_count = repository.book().count(filter).await?;
+
+    Ok(model::SavedSearch::new(saved_search, new_count))
+}
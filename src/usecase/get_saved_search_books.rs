@@ -0,0 +1,165 @@
+use std::sync::Arc;
+
+use hyper::{Body, Request};
+use serde::Deserialize;
+use util::validate::ValidatorNumberExt;
+use uuid::Uuid;
+
+use crate::{
+    error::UseCaseError,
+    model, payload,
+    repository::{
+        r#trait::{BookRepository, SavedSearchRepository},
+        RepositorySet,
+    },
+};
+
+use super::get_saved_search::filter_of;
+
+/// 필터를 제외하면 `get_books::Payload`와 같음
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct Payload {
+    #[serde(skip)]
+    pub user_id: Uuid,
+    #[serde(skip)]
+    pub saved_search_id: u64,
+    pub per_page: Option<usize>,
+    pub page: Option<usize>,
+    pub sort_by: Option<payload::BookSortBy>,
+    pub seed: Option<u32>,
+    /// `fields=id,title,kind`
+    pub fields: Option<String>,
+    pub include_tags: Option<bool>,
+    #[serde(skip)]
+    pub book_fields: model::BookFields,
+}
+
+impl Payload {
+    pub fn check(self) -> crate::Result<Self> {
+        let per_page = self
+            .per_page
+            .unwrap_or(25)
+            .validate()
+            .min(1)
+            .max(100)
+            .take()
+            .map_err(payload::Error::InvalidPerPage)?;
+
+        let page = self
+            .page
+            .unwrap_or(1)
+            .validate()
+            .min(1)
+            .take()
+            .map_err(payload::Error::InvalidPage)?;
+
+        let sort_by = self.sort_by.unwrap_or(payload::BookSortBy::IdDesc);
+
+        let seed = self.seed.unwrap_or_else(payload::random_seed);
+
+        let book_fields = payload::book_fields(self.fields.as_deref(), self.include_tags)?;
+
+        Ok(Self {
+            per_page: Some(per_page),
+            page: Some(page),
+            sort_by: Some(sort_by),
+            seed: Some(seed),
+            include_tags: Some(book_fields.tags),
+            book_fields,
+            ..self
+        })
+    }
+}
+
+/// (user_id, saved_search_id, request)
+impl TryFrom<(Uuid, u64, &mut Request<Body>)> for Payload {
+    type Error = crate::Error;
+
+    fn try_from(
+        (user_id, saved_search_id, request): (Uuid, u64, &mut Request<Body>),
+    ) -> Result<Self, Self::Error> {
+        let qs = request.uri().query().unwrap_or_default();
+        let payload: Payload =
+            serde_qs::from_str(qs).map_err(payload::Error::QuerystringDeserialize)?;
+
+        Payload {
+            user_id,
+            saved_search_id,
+            ..payload
+        }
+        .check()
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("Not found saved search")]
+    NotFoundSavedSearch,
+}
+
+impl From<Error> for crate::Error {
+    fn from(err: Error) -> Self {
+        UseCaseError::from(err).into()
+    }
+}
+
+pub type Model = model::BookPage;
+
+/// 응답한 작품들 중 가장 최근 작품까지 본 것으로 처리함
+pub async fn execute(
+    Payload {
+        user_id,
+        saved_search_id,
+        per_page,
+        page,
+        sort_by,
+        seed,
+        book_fields,
+        ..
+    }: Payload,
+    repository: Arc<RepositorySet>,
+) -> crate::Result<Model> {
+    let (per_page, page) = (per_page.unwrap(), page.unwrap());
+
+    let saved_search = repository
+        .saved_search()
+        .get_one(user_id, saved_search_id)
+        .await?
+        .ok_or(Error::NotFoundSavedSearch)?;
+
+    let filter = filter_of(&saved_search)?;
+
+    let books = repository
+        .book()
+        .get_many(
+            filter,
+            per_page,
+            page,
+            sort_by.unwrap().with_seed(seed.unwrap()),
+            book_fields.tags,
+        )
+        .await?;
+
+    // 응답하지 않은 작품이나, 목록을 읽은 뒤에 추가된 작품은 본 것으로 처리하지 않음
+    let last_seen_max_id = books.iter().map(|book| book.id).max().unwrap_or(0);
+
+    // 이름이나 필터는 건드리지 않아서 동시에 실행된 `PATCH`를 되돌리지 않음
+    if last_seen_max_id > saved_search.last_seen_max_id {
+        repository
+            .saved_search()
+            .mark_seen(user_id, saved_search_id, last_seen_max_id)
+            .await?;
+    }
+
+    Ok(model::BookPage {
+        books: books
+            .into_iter()
+            .map(|book| model::Book::from(book).project(book_fields))
+            .collect(),
+        total: None,
+        page,
+        per_page,
+        seed: matches!(sort_by, Some(payload::BookSortBy::Random)).then(|| seed.unwrap()),
+    })
+}
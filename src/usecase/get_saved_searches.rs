@@ -0,0 +1,42 @@
+use std::sync::Arc;
+
+use futures::{stream, StreamExt, TryStreamExt};
+use uuid::Uuid;
+
+use crate::{
+    error::UseCaseError,
+    model,
+    repository::{r#trait::SavedSearchRepository, RepositorySet},
+};
+
+use super::get_saved_search::with_new_count;
+
+#[derive(Debug)]
+pub struct Payload {
+    pub user_id: Uuid,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {}
+
+impl From<Error> for crate::Error {
+    fn from(err: Error) -> Self {
+        UseCaseError::from(err).into()
+    }
+}
+
+pub type Model = Vec<model::SavedSearch>;
+
+pub async fn execute(
+    Payload { user_id }: Payload,
+    repository: Arc<RepositorySet>,
+) -> crate::Result<Model> {
+    let saved_searches = repository.saved_search().get_many(user_id).await?;
+
+    // 새 작품 수를 세는 쿼리가 저장된 검색마다 하나씩 필요함
+    stream::iter(saved_searches)
+        .map(|saved_search| with_new_count(saved_search, Arc::clone(&repository)))
+        .buffered(4)
+        .try_collect()
+        .await
+}
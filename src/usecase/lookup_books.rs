@@ -0,0 +1,76 @@
+use std::{collections::HashMap, sync::Arc};
+
+use itertools::Itertools;
+use serde::Deserialize;
+
+use crate::{
+    error::UseCaseError,
+    model, payload,
+    repository::{r#trait::BookRepository, RepositorySet},
+};
+
+/// ```json
+/// { "ids": [1, 2, 3] }
+/// ```
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct Payload {
+    pub ids: Vec<u32>,
+}
+
+impl Payload {
+    /// `limit`은 `Config::books_lookup_limit`
+    pub fn check(self, limit: usize) -> crate::Result<Self> {
+        if self.ids.len() > limit {
+            return Err(payload::Error::TooManyIds(limit).into());
+        }
+
+        Ok(self)
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {}
+
+impl From<Error> for crate::Error {
+    fn from(err: Error) -> Self {
+        UseCaseError::from(err).into()
+    }
+}
+
+pub type Model = model::BookLookup;
+
+pub async fn execute(
+    Payload { ids }: Payload,
+    repository: Arc<RepositorySet>,
+) -> crate::Result<Model> {
+    // 요청한 순서를 유지하면서 중복을 제거함
+    let ids = ids.into_iter().unique().collect::<Vec<_>>();
+
+    if ids.is_empty() {
+        return Ok(model::BookLookup {
+            books: Vec::new(),
+            missing_ids: Vec::new(),
+        });
+    }
+
+    let mut found = repository
+        .book()
+        .get_many_by_ids_unordered(ids.clone(), true)
+        .await?
+        .into_iter()
+        .map(|book| (book.id, book))
+        .collect::<HashMap<_, _>>();
+
+    let mut books = Vec::with_capacity(found.len());
+    let mut missing_ids = Vec::new();
+
+    for id in ids {
+        match found.remove(&id) {
+            Some(book) => books.push(book.into()),
+            None => missing_ids.push(id),
+        }
+    }
+
+    Ok(model::BookLookup { books, missing_ids })
+}
@@ -1,4 +1,16 @@
 pub mod create_book;
+pub mod create_saved_search;
+pub mod delete_saved_search;
 pub mod get_book;
+pub mod get_book_cache_stats;
+pub mod get_book_facets;
+pub mod get_book_tags;
 pub mod get_books;
 pub mod get_books_by_ids;
+pub mod get_random_book;
+pub mod get_saved_search;
+pub mod get_saved_search_books;
+pub mod get_saved_searches;
+pub mod lookup_books;
+pub mod recompute_tag_stats;
+pub mod update_saved_search;
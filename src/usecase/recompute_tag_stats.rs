@@ -0,0 +1,35 @@
+use std::sync::Arc;
+
+use crate::{
+    error::UseCaseError,
+    model,
+    repository::{r#trait::BookRepository, RepositorySet},
+};
+
+/// 내부 요청에서만 실행함
+#[derive(Debug)]
+pub struct Payload {}
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {}
+
+impl From<Error> for crate::Error {
+    fn from(err: Error) -> Self {
+        UseCaseError::from(err).into()
+    }
+}
+
+pub type Model = model::TagStatsReport;
+
+/// 주기적으로 실행해서 미리 세어둔 태그 통계를 맞추고, 어긋나 있던 항목들을 보고함
+pub async fn execute(_: Payload, repository: Arc<RepositorySet>) -> crate::Result<Model> {
+    let drifts = repository.book().recompute_tag_stats().await?;
+
+    if !drifts.is_empty() {
+        log::warn!("tag stats drifted: {} entries", drifts.len());
+    }
+
+    Ok(model::TagStatsReport {
+        drifts: drifts.into_iter().map(Into::into).collect(),
+    })
+}
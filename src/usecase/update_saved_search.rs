@@ -0,0 +1,99 @@
+use std::sync::Arc;
+
+use chrono::Utc;
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::{
+    entity,
+    error::UseCaseError,
+    model, payload,
+    repository::{r#trait::SavedSearchRepository, RepositorySet},
+};
+
+use super::{
+    create_saved_search::check_name,
+    get_saved_search::{latest_book_id, with_new_count},
+};
+
+/// 보내지 않은 필드는 바꾸지 않음
+///
+/// 필터를 바꾸면 새 작품 수도 다시 셈
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct Payload {
+    #[serde(skip)]
+    pub user_id: Uuid,
+    #[serde(skip)]
+    pub saved_search_id: u64,
+    pub name: Option<String>,
+    pub filter: Option<payload::BookFilter>,
+}
+
+impl Payload {
+    pub fn check(self) -> crate::Result<Self> {
+        let name = self.name.as_deref().map(check_name).transpose()?;
+
+        if let Some(filter) = &self.filter {
+            filter.clone().check()?;
+        }
+
+        Ok(Self { name, ..self })
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("Not found saved search")]
+    NotFoundSavedSearch,
+}
+
+impl From<Error> for crate::Error {
+    fn from(err: Error) -> Self {
+        UseCaseError::from(err).into()
+    }
+}
+
+pub type Model = model::SavedSearch;
+
+pub async fn execute(
+    Payload {
+        user_id,
+        saved_search_id,
+        name,
+        filter,
+    }: Payload,
+    repository: Arc<RepositorySet>,
+) -> crate::Result<Model> {
+    let saved_search = repository
+        .saved_search()
+        .get_one(user_id, saved_search_id)
+        .await?
+        .ok_or(Error::NotFoundSavedSearch)?;
+
+    let saved_search = match filter {
+        Some(filter) => entity::SavedSearch {
+            last_seen_max_id: latest_book_id(filter.clone().check()?, &repository).await?,
+            filter: serde_json::to_string(&filter).expect("json serialize"),
+            ..saved_search
+        },
+        None => saved_search,
+    };
+
+    let saved_search = entity::SavedSearch {
+        name: name.unwrap_or(saved_search.name),
+        updated_at: Utc::now(),
+        ..saved_search
+    };
+
+    let updated = repository
+        .saved_search()
+        .update(saved_search.clone())
+        .await?;
+
+    if !updated {
+        return Err(Error::NotFoundSavedSearch.into());
+    }
+
+    with_new_count(saved_search, repository).await
+}